@@ -0,0 +1,257 @@
+use std::io;
+
+use web_sys::HtmlVideoElement;
+
+use crate::{
+    utils::{expand_rgba, video_size},
+    BrowserVideoCapture, CaptureMode,
+};
+
+/// RGBA frame of the animation shown for `delay` seconds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationFrame {
+    pub data: Vec<u8>,
+    pub delay: f64,
+}
+
+/// Recorder of a short sequence of captured frames into an animated GIF or APNG.
+///
+/// Frames are taken at most at the target rate until the frame limit is reached,
+/// every frame is shown until the timestamp of the next one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationRecorder {
+    limit: usize,
+    rate: f64,
+    mode: CaptureMode,
+    max_size: Option<(u32, u32)>,
+    dither: bool,
+    size: Option<(u32, u32)>,
+    frames: Vec<AnimationFrame>,
+    last: Option<f64>,
+}
+
+impl AnimationRecorder {
+    /// Create the recorder of `limit` frames taken at `rate` frames per second.
+    pub fn new(limit: usize, rate: f64) -> Self {
+        Self {
+            limit,
+            rate,
+            mode: CaptureMode::default(),
+            max_size: None,
+            dither: false,
+            size: None,
+            frames: Vec::new(),
+            last: None,
+        }
+    }
+
+    /// Set the capture mode used by [`AnimationRecorder::record`].
+    pub fn mode(mut self, mode: CaptureMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Limit the frame size used by [`AnimationRecorder::record`].
+    ///
+    /// With [`CaptureMode::Adjust`] the video frame is downscaled to fit
+    /// the limit keeping its aspect ratio, other modes have the capture area clamped.
+    pub fn max_size(mut self, width: u32, height: u32) -> Self {
+        self.max_size = Some((width, height));
+        self
+    }
+
+    /// Enable Floyd-Steinberg dithering of the GIF palette.
+    pub fn dither(mut self, dither: bool) -> Self {
+        self.dither = dither;
+        self
+    }
+
+    /// Get the frame size, `None` before the first frame.
+    pub fn size(&self) -> Option<(u32, u32)> {
+        self.size
+    }
+
+    /// Get the recorded frames.
+    pub fn frames(&self) -> &[AnimationFrame] {
+        &self.frames
+    }
+
+    /// Check if the frame limit is reached.
+    pub fn is_full(&self) -> bool {
+        self.frames.len() >= self.limit
+    }
+
+    /// Check if the frame taken at `timestamp` in seconds is due by the target rate.
+    pub fn is_due(&self, timestamp: f64) -> bool {
+        !self.is_full()
+            && self
+                .last
+                .is_none_or(|last| timestamp - last >= 1.0 / self.rate - 1e-6)
+    }
+
+    /// Append the raw frame with `channels` bytes per pixel taken at `timestamp` in seconds.
+    ///
+    /// Returns `false` when the frame is not due, the buffer does not hold the frame
+    /// or its size differs from the first frame.
+    pub fn push(&mut self, buffer: &[u8], size: (u32, u32), channels: u32, timestamp: f64) -> bool {
+        let length = (size.0 as usize)
+            .checked_mul(size.1 as usize)
+            .and_then(|area| area.checked_mul(channels as usize));
+        if !(1..=4).contains(&channels) || length != Some(buffer.len()) {
+            return false;
+        }
+        if !self.is_due(timestamp) || *self.size.get_or_insert(size) != size {
+            return false;
+        }
+
+        if let (Some(previous), Some(last)) = (self.frames.last_mut(), self.last) {
+            previous.delay = timestamp - last;
+        }
+
+        self.frames.push(AnimationFrame {
+            data: expand_rgba(buffer, channels),
+            delay: 1.0 / self.rate,
+        });
+        self.last = Some(timestamp);
+        true
+    }
+
+    /// Capture the frame from the video element taken at `timestamp` in seconds,
+    /// such as the video `currentTime`, when it is due by the target rate.
+    pub fn record(
+        &mut self,
+        capture: &(impl BrowserVideoCapture + ?Sized),
+        source: &HtmlVideoElement,
+        timestamp: f64,
+    ) -> bool {
+        let (sw, sh) = video_size(source);
+        if sw == 0 || sh == 0 || !self.is_due(timestamp) {
+            return false;
+        }
+
+        let mode = match (self.mode, self.max_size) {
+            (CaptureMode::Adjust, Some(max)) => {
+                let (width, height) = fit((sw, sh), max);
+                capture.set_capture_size(width, height);
+                CaptureMode::Fill
+            }
+            (mode, Some((mw, mh))) => {
+                let (width, height) = capture.capture_size();
+                capture.set_capture_size(width.min(mw), height.min(mh));
+                mode
+            }
+            (mode, None) => mode,
+        };
+
+        let size = capture.capture(source, mode);
+        self.push(&capture.packed_data(), size, capture.channels_count(), timestamp)
+    }
+
+    /// Encode the frames into the looped animated GIF.
+    ///
+    /// Every frame has its own palette of up to 256 colors, the alpha channel is ignored.
+    /// Frames larger than `65535` in either dimension fail with the limit `ImageError`.
+    #[cfg(feature = "gif")]
+    pub fn encode_gif(&self) -> io::Result<Vec<u8>> {
+        let (width, height) = self.size.unwrap_or((1, 1));
+        if width > u16::MAX as u32 || height > u16::MAX as u32 {
+            return Err(io::Error::other(image::ImageError::Limits(
+                image::error::LimitError::from_kind(image::error::LimitErrorKind::DimensionError),
+            )));
+        }
+        let (width, height) = (width as u16, height as u16);
+        let mut output = Vec::new();
+
+        {
+            let mut encoder = gif::Encoder::new(&mut output, width, height, &[])
+                .map_err(io::Error::other)?;
+            encoder
+                .set_repeat(gif::Repeat::Infinite)
+                .map_err(io::Error::other)?;
+
+            for frame in self.frames.iter() {
+                let (palette, indices) =
+                    quantize(&frame.data, (width.into(), height.into()), self.dither)?;
+                encoder
+                    .write_frame(&gif::Frame {
+                        width,
+                        height,
+                        delay: (frame.delay * 100.0).round().clamp(1.0, u16::MAX as f64) as u16,
+                        palette: Some(palette),
+                        buffer: indices.into(),
+                        ..Default::default()
+                    })
+                    .map_err(io::Error::other)?;
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Encode the frames into the looped animated PNG.
+    #[cfg(feature = "apng")]
+    pub fn encode_apng(&self) -> io::Result<Vec<u8>> {
+        let (width, height) = self.size.unwrap_or((1, 1));
+        let mut output = Vec::new();
+
+        let mut encoder = png::Encoder::new(&mut output, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .set_animated(self.frames.len().max(1) as u32, 0)
+            .map_err(io::Error::other)?;
+
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+        for frame in self.frames.iter() {
+            writer
+                .set_frame_delay(
+                    (frame.delay * 1000.0).round().min(u16::MAX as f64) as u16,
+                    1000,
+                )
+                .map_err(io::Error::other)?;
+            writer
+                .write_image_data(&frame.data)
+                .map_err(io::Error::other)?;
+        }
+        writer.finish().map_err(io::Error::other)?;
+
+        Ok(output)
+    }
+}
+
+/// Fit the size into the limit keeping the aspect ratio, it is never upscaled.
+fn fit((width, height): (u32, u32), (mw, mh): (u32, u32)) -> (u32, u32) {
+    let scale = (mw as f64 / width as f64)
+        .min(mh as f64 / height as f64)
+        .min(1.0);
+    (
+        ((width as f64 * scale).round() as u32).max(1),
+        ((height as f64 * scale).round() as u32).max(1),
+    )
+}
+
+/// Reduce the RGBA frame to the RGB palette and the palette indices.
+#[cfg(feature = "gif")]
+fn quantize(
+    data: &[u8],
+    (width, height): (u32, u32),
+    dither: bool,
+) -> io::Result<(Vec<u8>, Vec<u8>)> {
+    let opaque: Vec<u8> = data
+        .chunks_exact(4)
+        .flat_map(|pixel| [pixel[0], pixel[1], pixel[2], 255])
+        .collect();
+    let quant = color_quant::NeuQuant::new(10, 256, &opaque);
+
+    let mut frame = image::RgbaImage::from_raw(width, height, opaque)
+        .ok_or_else(|| io::Error::other("Frame size does not match the recorder"))?;
+    if dither {
+        image::imageops::dither(&mut frame, &quant);
+    }
+
+    let indices = frame
+        .pixels()
+        .map(|pixel| quant.index_of(&pixel.0) as u8)
+        .collect();
+    Ok((quant.color_map_rgb(), indices))
+}
@@ -0,0 +1,271 @@
+/// Rectangle on the capture area in pixels with the top-left origin.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub const fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Get the size of the rectangle in pixels.
+    pub const fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Get the area of the rectangle in pixels.
+    pub const fn area(&self) -> u32 {
+        self.width * self.height
+    }
+
+    /// Get the byte offset of the top-left pixel in the buffer with `stride` bytes per row.
+    pub const fn offset(&self, stride: usize, channels: usize) -> usize {
+        self.y as usize * stride + self.x as usize * channels
+    }
+
+    /// Get the overlapping part of the rectangle placed at `(x, y)` with size
+    /// `(width, height)`, possibly out of the capture area, and this rectangle.
+    pub fn clip(&self, x: i32, y: i32, width: u32, height: u32) -> Rect {
+        let left = (x as i64).max(self.x as i64);
+        let top = (y as i64).max(self.y as i64);
+        let right = (x as i64 + width as i64).min(self.x as i64 + self.width as i64);
+        let bottom = (y as i64 + height as i64).min(self.y as i64 + self.height as i64);
+
+        if right <= left || bottom <= top {
+            Rect::default()
+        } else {
+            Rect::new(
+                left as u32,
+                top as u32,
+                (right - left) as u32,
+                (bottom - top) as u32,
+            )
+        }
+    }
+
+    /// Copy the pixels of the rectangle from the buffer of `width` pixels per row
+    /// into the tightly packed `target`.
+    pub fn copy_from(&self, buffer: &[u8], width: u32, channels: usize, target: &mut [u8]) {
+        let stride = width as usize * channels;
+        let row = self.width as usize * channels;
+
+        for (y, target) in target
+            .chunks_exact_mut(row)
+            .take(self.height as usize)
+            .enumerate()
+        {
+            let start = self.offset(stride, channels) + y * stride;
+            target.copy_from_slice(&buffer[start..start + row]);
+        }
+    }
+}
+
+/// Layout of the frame downscaled by powers of two in one capture area.
+///
+/// The first level is at the top-left corner and the rest
+/// are stacked in a column at its right side.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pyramid {
+    levels: Vec<Rect>,
+}
+
+impl Pyramid {
+    /// Create the layout with the first level of size `(width, height)`
+    /// and `levels` levels in total, each next one half of the previous.
+    pub fn new(width: u32, height: u32, levels: u32) -> Self {
+        let mut y = 0;
+        let levels = (0..levels.max(1))
+            .map(|level| {
+                let (w, h) = ((width >> level).max(1), (height >> level).max(1));
+                match level {
+                    0 => Rect::new(0, 0, w, h),
+                    _ => {
+                        y += h;
+                        Rect::new(width, y - h, w, h)
+                    }
+                }
+            })
+            .collect();
+
+        Self { levels }
+    }
+
+    /// Get the number of levels.
+    pub fn len(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Always `false`, there is at least one level.
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty()
+    }
+
+    /// Get the rectangles of the levels on the capture area.
+    pub fn levels(&self) -> &[Rect] {
+        &self.levels
+    }
+
+    /// Get the rectangle of the level on the capture area.
+    pub fn level(&self, level: usize) -> Option<Rect> {
+        self.levels.get(level).copied()
+    }
+
+    /// Get the size of the capture area holding all levels.
+    pub fn size(&self) -> (u32, u32) {
+        self.levels.iter().fold((0, 0), |(w, h), rect| {
+            (w.max(rect.x + rect.width), h.max(rect.y + rect.height))
+        })
+    }
+
+    /// Get the offsets of the levels in the buffer packed by [`Pyramid::pack`].
+    pub fn packed_offsets(&self, channels: usize) -> Vec<usize> {
+        self.levels
+            .iter()
+            .scan(0, |offset, rect| {
+                let current = *offset;
+                *offset += rect.area() as usize * channels;
+                Some(current)
+            })
+            .collect()
+    }
+
+    /// Copy the level out of the captured `atlas` buffer.
+    pub fn copy_level(&self, atlas: &[u8], level: usize, channels: usize) -> Option<Vec<u8>> {
+        let rect = self.level(level)?;
+        let mut target = vec![0; rect.area() as usize * channels];
+        rect.copy_from(atlas, self.size().0, channels, &mut target);
+        Some(target)
+    }
+
+    /// Pack all levels of the captured `atlas` buffer one after another.
+    pub fn pack(&self, atlas: &[u8], channels: usize) -> Vec<u8> {
+        let width = self.size().0;
+        let area: u32 = self.levels.iter().map(Rect::area).sum();
+        let mut target = vec![0; area as usize * channels];
+
+        for (rect, offset) in self.levels.iter().zip(self.packed_offsets(channels)) {
+            let length = rect.area() as usize * channels;
+            rect.copy_from(atlas, width, channels, &mut target[offset..offset + length]);
+        }
+
+        target
+    }
+}
+
+/// Layout of tiles in one capture area, each tile holds one video source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mosaic {
+    tiles: Vec<Rect>,
+}
+
+impl Mosaic {
+    /// Create the layout from arbitrary tiles.
+    pub fn new(tiles: Vec<Rect>) -> Self {
+        Self { tiles }
+    }
+
+    /// Create the layout of `columns` by `rows` tiles of the same size
+    /// ordered row by row.
+    pub fn grid(columns: u32, rows: u32, tile_width: u32, tile_height: u32) -> Self {
+        Self::new(
+            (0..rows)
+                .flat_map(|row| {
+                    (0..columns).map(move |column| {
+                        Rect::new(
+                            column * tile_width,
+                            row * tile_height,
+                            tile_width,
+                            tile_height,
+                        )
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    /// Get the number of tiles.
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// Check if the layout has no tiles.
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// Get the tiles on the capture area.
+    pub fn tiles(&self) -> &[Rect] {
+        &self.tiles
+    }
+
+    /// Get the tile on the capture area.
+    pub fn tile(&self, index: usize) -> Option<Rect> {
+        self.tiles.get(index).copied()
+    }
+
+    /// Get the size of the capture area holding all tiles.
+    pub fn size(&self) -> (u32, u32) {
+        self.tiles.iter().fold((0, 0), |(w, h), rect| {
+            (w.max(rect.x + rect.width), h.max(rect.y + rect.height))
+        })
+    }
+
+    /// Get where the source of `source_size` is drawn for the tile with the `mode`
+    /// as `(x, y, width, height)` and the visible part of it clipped by the tile.
+    ///
+    /// Tiles have a fixed size, so [`CaptureMode::Adjust`](crate::CaptureMode::Adjust)
+    /// behaves as [`CaptureMode::Fill`](crate::CaptureMode::Fill).
+    /// [`CaptureMode::Pinhole`](crate::CaptureMode::Pinhole) covers the tile
+    /// with the scaled source matching their centers.
+    pub fn placement(
+        &self,
+        index: usize,
+        (sw, sh): (u32, u32),
+        mode: crate::CaptureMode,
+    ) -> Option<((i32, i32, u32, u32), Rect)> {
+        let tile = self.tile(index)?;
+
+        let (x, y, width, height) = match mode {
+            crate::CaptureMode::Put(x, y) => (tile.x as i32 + x, tile.y as i32 + y, sw, sh),
+            crate::CaptureMode::Fill | crate::CaptureMode::Adjust => {
+                (tile.x as i32, tile.y as i32, tile.width, tile.height)
+            }
+            crate::CaptureMode::Pinhole => {
+                let scale = (tile.width as f64 / sw as f64).max(tile.height as f64 / sh as f64);
+                if !scale.is_normal() {
+                    return Some(((tile.x as i32, tile.y as i32, 0, 0), Rect::default()));
+                }
+
+                let (width, height) = (
+                    (sw as f64 * scale).round() as u32,
+                    (sh as f64 * scale).round() as u32,
+                );
+                (
+                    tile.x as i32 + (tile.width as i32 - width as i32) / 2,
+                    tile.y as i32 + (tile.height as i32 - height as i32) / 2,
+                    width,
+                    height,
+                )
+            }
+        };
+
+        Some(((x, y, width, height), tile.clip(x, y, width, height)))
+    }
+
+    /// Copy the tile out of the captured `atlas` buffer.
+    pub fn copy_tile(&self, atlas: &[u8], index: usize, channels: usize) -> Option<Vec<u8>> {
+        let rect = self.tile(index)?;
+        let mut target = vec![0; rect.area() as usize * channels];
+        rect.copy_from(atlas, self.size().0, channels, &mut target);
+        Some(target)
+    }
+}
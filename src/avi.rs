@@ -0,0 +1,253 @@
+use std::io::{self, Seek, SeekFrom, Write};
+
+#[cfg(feature = "jpeg")]
+use crate::BrowserVideoCapture;
+
+const AVIF_HASINDEX: u32 = 0x10;
+const AVIIF_KEYFRAME: u32 = 0x10;
+
+/// Size of the headers before the `movi` list data.
+const HEADER_SIZE: u64 = 224;
+
+/// Writer of JPEG frames into the Motion-JPEG AVI file.
+///
+/// The stream has a constant frame rate, frames written with timestamps
+/// are placed in their slots and the gaps are filled with empty chunks,
+/// which players show as the repeated previous frame.
+#[derive(Debug)]
+pub struct AviWriter<W: Write + Seek> {
+    writer: W,
+    size: (u32, u32),
+    rate: (u32, u32),
+    quality: u8,
+    start: Option<f64>,
+    /// Offset from the `movi` list type and size of every chunk.
+    index: Vec<(u32, u32)>,
+    movi: u32,
+}
+
+impl<W: Write + Seek> AviWriter<W> {
+    /// Create the writer of frames of `size` with the frame `rate` as `(numerator, denominator)`.
+    ///
+    /// The headers are written now and updated by [`AviWriter::finish`].
+    pub fn new(mut writer: W, size: (u32, u32), rate: (u32, u32)) -> io::Result<Self> {
+        writer.write_all(&header(size, rate, 0, 0, 0))?;
+
+        Ok(Self {
+            writer,
+            size,
+            rate,
+            quality: 90,
+            start: None,
+            index: Vec::new(),
+            movi: 4,
+        })
+    }
+
+    /// Set the JPEG quality from `1` to `100` of the encoded frames.
+    pub fn quality(mut self, quality: u8) -> Self {
+        self.quality = quality;
+        self
+    }
+
+    /// Get the number of written frames including the empty ones.
+    pub fn frames(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Get the frame size.
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    fn write_chunk(&mut self, data: &[u8]) -> io::Result<()> {
+        let size = data.len() as u32;
+        self.writer.write_all(b"00dc")?;
+        self.writer.write_all(&size.to_le_bytes())?;
+        self.writer.write_all(data)?;
+        if size % 2 == 1 {
+            self.writer.write_all(&[0])?;
+        }
+
+        self.index.push((self.movi, size));
+        self.movi += 8 + size + size % 2;
+        Ok(())
+    }
+
+    /// Append the JPEG frame into the next slot.
+    pub fn write_jpeg(&mut self, jpeg: &[u8]) -> io::Result<()> {
+        self.write_chunk(jpeg)
+    }
+
+    /// Append the JPEG frame taken at `timestamp` in seconds, such as the video
+    /// `currentTime`, the frame is dropped when its slot is already taken.
+    pub fn write_jpeg_at(&mut self, jpeg: &[u8], timestamp: f64) -> io::Result<()> {
+        let start = *self.start.get_or_insert(timestamp);
+        let (numerator, denominator) = (self.rate.0 as f64, self.rate.1.max(1) as f64);
+        let slot = ((timestamp - start) * numerator / denominator).round();
+
+        if slot < self.index.len() as f64 {
+            return Ok(());
+        }
+
+        while (self.index.len() as f64) < slot {
+            self.write_chunk(&[])?;
+        }
+
+        self.write_chunk(jpeg)
+    }
+
+    #[cfg(feature = "jpeg")]
+    fn encode(&self, buffer: &[u8], channels: u32) -> io::Result<Vec<u8>> {
+        let color = match channels {
+            1 => image::ColorType::L8,
+            2 => image::ColorType::La8,
+            3 => image::ColorType::Rgb8,
+            4 => image::ColorType::Rgba8,
+            _ => panic!("Unsupported channels count"),
+        };
+        crate::encode_jpeg(buffer, self.size, color, self.quality).map_err(io::Error::other)
+    }
+
+    /// Encode and append the raw frame with `channels` bytes per pixel.
+    #[cfg(feature = "jpeg")]
+    pub fn write(&mut self, buffer: &[u8], channels: u32) -> io::Result<()> {
+        let jpeg = self.encode(buffer, channels)?;
+        self.write_jpeg(&jpeg)
+    }
+
+    /// Encode and append the raw frame taken at `timestamp` in seconds.
+    #[cfg(feature = "jpeg")]
+    pub fn write_at(&mut self, buffer: &[u8], channels: u32, timestamp: f64) -> io::Result<()> {
+        let jpeg = self.encode(buffer, channels)?;
+        self.write_jpeg_at(&jpeg, timestamp)
+    }
+
+    /// Encode and append the frame retrieved from the capture taken at `timestamp` in seconds.
+    #[cfg(feature = "jpeg")]
+    pub fn write_capture_at(
+        &mut self,
+        capture: &(impl BrowserVideoCapture + ?Sized),
+        timestamp: f64,
+    ) -> io::Result<()> {
+        if capture.capture_size() != self.size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Capture size does not match the stream",
+            ));
+        }
+        self.write_at(&capture.packed_data(), capture.channels_count(), timestamp)
+    }
+
+    /// Write the index, update the headers and get the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.write_all(b"idx1")?;
+        self.writer
+            .write_all(&(self.index.len() as u32 * 16).to_le_bytes())?;
+        for &(offset, size) in self.index.iter() {
+            let flags = if size > 0 { AVIIF_KEYFRAME } else { 0 };
+            self.writer.write_all(b"00dc")?;
+            for value in [flags, offset, size].iter() {
+                self.writer.write_all(&value.to_le_bytes())?;
+            }
+        }
+
+        let largest = self.index.iter().map(|&(_, size)| size).max().unwrap_or(0);
+        let total = HEADER_SIZE as u32 + self.movi - 4 + 8 + self.index.len() as u32 * 16;
+
+        self.writer.seek(SeekFrom::Start(0))?;
+        self.writer.write_all(&header(
+            self.size,
+            self.rate,
+            self.index.len() as u32,
+            largest,
+            self.movi,
+        ))?;
+        self.writer.seek(SeekFrom::Start(4))?;
+        self.writer.write_all(&(total - 8).to_le_bytes())?;
+        self.writer.seek(SeekFrom::End(0))?;
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+}
+
+/// Build the headers up to the `movi` list data with its size of `movi` bytes.
+fn header(
+    (width, height): (u32, u32),
+    (numerator, denominator): (u32, u32),
+    frames: u32,
+    largest: u32,
+    movi: u32,
+) -> Vec<u8> {
+    let mut data = Vec::with_capacity(HEADER_SIZE as usize);
+    let mut put = |bytes: &[u8]| data.extend_from_slice(bytes);
+
+    let period = (1_000_000.0 * denominator as f64 / numerator.max(1) as f64).round() as u32;
+    let rate = (largest as u64 * numerator as u64 / denominator.max(1) as u64) as u32;
+
+    put(b"RIFF");
+    put(&0u32.to_le_bytes());
+    put(b"AVI ");
+
+    put(b"LIST");
+    put(&192u32.to_le_bytes());
+    put(b"hdrl");
+
+    put(b"avih");
+    put(&56u32.to_le_bytes());
+    for value in [
+        period,
+        rate,
+        0,
+        AVIF_HASINDEX,
+        frames,
+        0,
+        1,
+        largest,
+        width,
+        height,
+        0,
+        0,
+        0,
+        0,
+    ]
+    .iter()
+    {
+        put(&value.to_le_bytes());
+    }
+
+    put(b"LIST");
+    put(&116u32.to_le_bytes());
+    put(b"strl");
+
+    put(b"strh");
+    put(&56u32.to_le_bytes());
+    put(b"vids");
+    put(b"MJPG");
+    put(&0u32.to_le_bytes());
+    put(&[0; 4]);
+    for value in [0, denominator, numerator, 0, frames, largest, u32::MAX, 0].iter() {
+        put(&value.to_le_bytes());
+    }
+    for value in [0, 0, width as u16, height as u16].iter() {
+        put(&value.to_le_bytes());
+    }
+
+    put(b"strf");
+    put(&40u32.to_le_bytes());
+    put(&40u32.to_le_bytes());
+    put(&width.to_le_bytes());
+    put(&height.to_le_bytes());
+    put(&1u16.to_le_bytes());
+    put(&24u16.to_le_bytes());
+    put(b"MJPG");
+    put(&(width * height * 3).to_le_bytes());
+    put(&[0; 16]);
+
+    put(b"LIST");
+    put(&movi.to_le_bytes());
+    put(b"movi");
+
+    data
+}
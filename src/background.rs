@@ -0,0 +1,176 @@
+use crate::{stats::luma, BrowserVideoCapture};
+
+/// Variance of the new background pixel.
+pub(crate) const INITIAL_VARIANCE: f32 = 100.0;
+/// Lower bound of the background variance, so the noise of the static scene
+/// does not turn into the foreground.
+pub(crate) const MIN_VARIANCE: f32 = 16.0;
+
+/// Options of the background model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BackgroundOptions {
+    /// Weight of the new frame in the running average from `0` to `1`.
+    pub learning_rate: f32,
+    /// Distance from the background mean in standard deviations
+    /// of the foreground pixel.
+    pub threshold: f32,
+}
+
+impl Default for BackgroundOptions {
+    fn default() -> Self {
+        Self {
+            learning_rate: 0.02,
+            threshold: 2.5,
+        }
+    }
+}
+
+impl BackgroundOptions {
+    pub fn learning_rate(mut self, value: f32) -> Self {
+        self.learning_rate = value.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn threshold(mut self, value: f32) -> Self {
+        self.threshold = value;
+        self
+    }
+}
+
+/// Background model with the running Gaussian of the luminance of every pixel.
+///
+/// WebGL2 captures keep the model in float textures and read back only
+/// the foreground mask, other captures update it on the CPU.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackgroundModel {
+    options: BackgroundOptions,
+    size: (u32, u32),
+    mean: Vec<f32>,
+    variance: Vec<f32>,
+    mask: Vec<u8>,
+    frame: Vec<u8>,
+    /// The model kept by the capture should be reset too.
+    reset: bool,
+}
+
+impl Default for BackgroundModel {
+    fn default() -> Self {
+        Self::new(BackgroundOptions::default())
+    }
+}
+
+impl BackgroundModel {
+    pub fn new(options: BackgroundOptions) -> Self {
+        Self {
+            options,
+            size: (0, 0),
+            mean: Vec::new(),
+            variance: Vec::new(),
+            mask: Vec::new(),
+            frame: Vec::new(),
+            reset: true,
+        }
+    }
+
+    pub fn options(&self) -> &BackgroundOptions {
+        &self.options
+    }
+
+    /// Set the options applied from the next frame.
+    pub fn set_options(&mut self, options: BackgroundOptions) {
+        self.options = options;
+    }
+
+    /// Get the foreground mask of the last frame, `255` for the foreground pixel.
+    pub fn mask(&self) -> &[u8] {
+        &self.mask
+    }
+
+    /// Get the fraction of the foreground pixels of the last frame.
+    pub fn foreground_ratio(&self) -> f64 {
+        match self.mask.len() {
+            0 => 0.0,
+            area => self.mask.iter().filter(|&&value| value > 0).count() as f64 / area as f64,
+        }
+    }
+
+    /// Forget the background.
+    pub fn reset(&mut self) {
+        self.size = (0, 0);
+        self.mean.clear();
+        self.variance.clear();
+        self.mask.clear();
+        self.reset = true;
+    }
+
+    /// Update the model with the raw frame with `channels` bytes per pixel
+    /// and get the foreground ratio, the first frame becomes the background.
+    pub fn update(&mut self, buffer: &[u8], size: (u32, u32), channels: u32) -> f64 {
+        let area = (size.0 * size.1) as usize;
+        let initialize = self.size != size;
+        if initialize {
+            self.size = size;
+            self.mean.resize(area, 0.0);
+            self.variance.resize(area, INITIAL_VARIANCE);
+            self.variance.fill(INITIAL_VARIANCE);
+        }
+        self.mask.resize(area, 0);
+
+        let BackgroundOptions {
+            learning_rate,
+            threshold,
+        } = self.options;
+        let pixels = buffer.chunks_exact(channels as usize).take(area);
+
+        for (index, pixel) in pixels.enumerate() {
+            let value = match *pixel {
+                [l] | [l, _] => l,
+                [r, g, b, ..] => luma(r, g, b),
+                [] => unreachable!(),
+            } as f32;
+
+            if initialize {
+                self.mean[index] = value;
+                self.mask[index] = 0;
+                continue;
+            }
+
+            let diff = value - self.mean[index];
+            let variance = self.variance[index].max(MIN_VARIANCE);
+            let foreground = diff * diff > threshold * threshold * variance;
+
+            self.mask[index] = if foreground { 255 } else { 0 };
+            self.mean[index] += learning_rate * diff;
+            self.variance[index] =
+                (variance + learning_rate * (diff * diff - variance)).max(MIN_VARIANCE);
+        }
+
+        self.foreground_ratio()
+    }
+
+    /// Update the model with the captured frame and get the foreground ratio.
+    pub fn update_capture(&mut self, capture: &(impl BrowserVideoCapture + ?Sized)) -> f64 {
+        let (width, height) = capture.capture_size();
+        let channels = capture.channels_count();
+
+        if std::mem::take(&mut self.reset) {
+            capture.reset_background();
+        }
+        if let Some(mask) = capture.update_background(self.options) {
+            // the model on the CPU is stale from now
+            self.size = (0, 0);
+            self.mask = mask;
+            return self.foreground_ratio();
+        }
+
+        let mut frame = std::mem::take(&mut self.frame);
+        frame.resize((width * height * channels) as usize, 0);
+        if !frame.is_empty() {
+            capture.retrieve_rect(0, 0, width, height, &mut frame);
+        }
+
+        let ratio = self.update(&frame, (width, height), channels);
+        self.frame = frame;
+        ratio
+    }
+}
@@ -0,0 +1,100 @@
+use std::fmt::Display;
+
+use wasm_bindgen_futures::JsFuture;
+#[cfg(any(feature = "html", feature = "offscreen"))]
+use web_sys::wasm_bindgen::{JsCast, JsValue};
+use web_sys::{js_sys, Blob};
+
+/// Image format of the browser encoder.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BlobFormat {
+    #[default]
+    Png,
+    Jpeg,
+    Webp,
+}
+
+impl Display for BlobFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BlobFormat::Png => write!(f, "image/png"),
+            BlobFormat::Jpeg => write!(f, "image/jpeg"),
+            BlobFormat::Webp => write!(f, "image/webp"),
+        }
+    }
+}
+
+/// Options of the browser image encoder.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct BlobOptions {
+    pub format: BlobFormat,
+    /// Quality from `0.0` to `1.0` for the lossy formats,
+    /// the browser default is used when not set.
+    pub quality: Option<f64>,
+}
+
+impl BlobOptions {
+    pub fn format(mut self, value: BlobFormat) -> Self {
+        self.format = value;
+        self
+    }
+
+    pub fn quality(mut self, value: f64) -> Self {
+        self.quality = Some(value);
+        self
+    }
+}
+
+/// Canvas which can encode its content with the browser image encoder.
+#[cfg(any(feature = "html", feature = "offscreen"))]
+pub(crate) trait BlobCanvas {
+    fn blob_promise(&self, options: BlobOptions) -> Result<js_sys::Promise, JsValue>;
+}
+
+#[cfg(feature = "html")]
+impl BlobCanvas for web_sys::HtmlCanvasElement {
+    fn blob_promise(&self, options: BlobOptions) -> Result<js_sys::Promise, JsValue> {
+        let mut result = Ok(());
+        let promise = js_sys::Promise::new(&mut |resolve, _| {
+            result = self.to_blob_with_type_and_encoder_options(
+                &resolve,
+                &options.format.to_string(),
+                &options.quality.map(JsValue::from).unwrap_or_default(),
+            );
+        });
+        result.map(|_| promise)
+    }
+}
+
+#[cfg(feature = "offscreen")]
+impl BlobCanvas for web_sys::OffscreenCanvas {
+    fn blob_promise(&self, options: BlobOptions) -> Result<js_sys::Promise, JsValue> {
+        let encode = web_sys::ImageEncodeOptions::new();
+        encode.set_type(&options.format.to_string());
+        if let Some(quality) = options.quality {
+            encode.set_quality(quality);
+        }
+        self.convert_to_blob_with_options(&encode)
+    }
+}
+
+/// Encode the content of the canvas into the blob.
+#[cfg(any(feature = "html", feature = "offscreen"))]
+pub(crate) async fn canvas_blob(
+    canvas: &impl BlobCanvas,
+    options: BlobOptions,
+) -> Result<Blob, js_sys::Error> {
+    let value = JsFuture::from(canvas.blob_promise(options)?).await?;
+
+    // `toBlob` passes `null` to the callback when the encoding fails
+    value
+        .dyn_into::<Blob>()
+        .map_err(|_| js_sys::Error::new("Failed to encode the canvas"))
+}
+
+/// Read the content of the blob.
+pub async fn blob_bytes(blob: &Blob) -> Result<Vec<u8>, js_sys::Error> {
+    let buffer = JsFuture::from(blob.array_buffer()).await?;
+    Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+}
@@ -1,43 +1,213 @@
-use std::fmt::Display;
+use std::{
+    cell::{Cell, RefCell},
+    fmt::Display,
+};
 
 use web_sys::{
     js_sys,
-    wasm_bindgen::{JsCast, JsValue},
+    wasm_bindgen::{Clamped, JsCast, JsValue},
 };
 
 use crate::{BrowserVideoCapture, CaptureArea};
 
+/// Image data coordinates, the unstable web-sys APIs declare them as integers.
+#[cfg(not(web_sys_unstable_apis))]
+type ImageDataCoord = f64;
+#[cfg(web_sys_unstable_apis)]
+type ImageDataCoord = i32;
+
+/// Copy the visible `rect` of the RGBA image placed at `(x, y, width, height)`
+/// into the atlas of `atlas_width`.
+fn paste(atlas: &mut [u8], atlas_width: u32, image: &[u8], (x, y, width, _): (i32, i32, u32, u32), rect: crate::Rect) {
+    let row = rect.width as usize * 4;
+    for i in 0..rect.height as usize {
+        let source = (((rect.y as i64 - y as i64) as usize + i) * width as usize + (rect.x as i64 - x as i64) as usize) * 4;
+        let target = ((rect.y as usize + i) * atlas_width as usize + rect.x as usize) * 4;
+        atlas[target..target + row].copy_from_slice(&image[source..source + row]);
+    }
+}
+
 macro_rules! impl_capture_2d {
     ($name:tt $canvas:ty, $context:ty, $options:ty) => {
-        #[derive(Debug, Clone, PartialEq, Eq)]
+        #[derive(Debug, Clone)]
         pub struct $name {
             canvas: $canvas,
             context: $context,
+            #[allow(dead_code)]
             color: crate::CaptureColor,
+            resample: Cell<crate::Resample>,
+            layout: Cell<crate::FrameLayout>,
+            temporal: RefCell<Option<crate::TemporalFilter>>,
+            undistort: RefCell<Option<crate::Undistort>>,
+            remap: RefCell<Option<crate::RemapTable>>,
         }
 
+        /// Captures are the same when they draw into the same context.
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                self.canvas == other.canvas && self.context == other.context
+            }
+        }
+
+        impl Eq for $name {}
+
         impl $name {
             pub fn new(canvas: $canvas, context: $context, color: crate::CaptureColor) -> Self {
-                Self { canvas, context, color }
+                Self {
+                    canvas,
+                    context,
+                    color,
+                    resample: Cell::new(Default::default()),
+                    layout: Cell::new(Default::default()),
+                    temporal: RefCell::new(None),
+                    undistort: RefCell::new(None),
+                    remap: RefCell::new(None),
+                }
             }
 
             pub fn validate(self) -> Result<Self, Option<String>> {
                 Ok(self)
             }
 
+            fn read_into(&self, x: i32, y: i32, width: u32, height: u32, buffer: &mut [u8]) {
+                let image_data = self.context
+                    .get_image_data(
+                        x as ImageDataCoord,
+                        y as ImageDataCoord,
+                        width as ImageDataCoord,
+                        height as ImageDataCoord,
+                    )
+                    .unwrap();
+                // `ImageData::data` copies the pixels into the new vector,
+                // so copy them straight from the clamped array instead
+                js_sys::Reflect::get(&image_data, &JsValue::from("data"))
+                    .unwrap()
+                    .unchecked_into::<js_sys::Uint8ClampedArray>()
+                    .copy_to(buffer);
+            }
+
             fn read_data(&self, x: i32, y: i32, width: u32, height: u32) -> Vec<u8> {
-                let image_data =
-                    self.context
-                        .get_image_data(x as f64, y as f64, width as f64, height as f64).unwrap();
-                image_data.data().0
+                let mut buffer = vec![0; (width * height * 4) as usize];
+                self.read_into(x, y, width, height, &mut buffer);
+                buffer
+            }
+
+            /// Check if the filter has no browser equivalent, so scaled draws
+            /// resample the frame on the CPU.
+            fn resample_on_cpu(&self) -> bool {
+                matches!(
+                    self.resample.get(),
+                    crate::Resample::Area | crate::Resample::Bicubic | crate::Resample::Lanczos
+                )
+            }
+
+            /// Draw the whole video frame on the canvas and read it back,
+            /// the canvas is left resized to the video size.
+            fn read_frame(&self, source: &web_sys::HtmlVideoElement) -> Vec<u8> {
+                let (sw, sh) = crate::utils::video_size(source);
+                self.set_capture_size(sw, sh);
+                self.context.draw_image_with_html_video_element(source, 0.0, 0.0).unwrap();
+                self.read_data(0, 0, sw, sh)
+            }
+
+            fn put_data(&self, data: &[u8], (width, height): (u32, u32), (x, y): (i32, i32)) {
+                let image_data = web_sys::ImageData::new_with_u8_clamped_array_and_sh(
+                    Clamped(data),
+                    width,
+                    height,
+                )
+                .unwrap();
+                self.context.put_image_data(&image_data, x as ImageDataCoord, y as ImageDataCoord).unwrap();
+            }
+
+            fn set_smoothing(&self) {
+                // the browser picks the interpolation, the quality only hints
+                // at the filter closest to the requested one
+                let quality = match self.resample.get() {
+                    crate::Resample::Nearest => None,
+                    crate::Resample::Bilinear => Some("low"),
+                    crate::Resample::Area => Some("medium"),
+                    crate::Resample::Bicubic | crate::Resample::Lanczos => Some("high"),
+                };
+                self.context.set_image_smoothing_enabled(quality.is_some());
+                if let Some(quality) = quality {
+                    js_set!(self.context, "imageSmoothingQuality", quality);
+                }
+            }
+
+            /// Draw the whole video frame on the canvas, read it back and put the frame
+            /// of size `(vw, vh)` produced by `render` at the place given by `mode`.
+            fn render_frame(
+                &self,
+                source: &web_sys::HtmlVideoElement,
+                (vw, vh): (u32, u32),
+                mode: crate::CaptureMode,
+                render: impl FnOnce(&[u8], (u32, u32), &mut [u8], (u32, u32)),
+            ) -> (u32, u32) {
+                let (sw, sh) = crate::utils::video_size(source);
+                let (cw, ch) = self.capture_size();
+
+                let ((x, y), (dw, dh), (cw, ch)) = match mode {
+                    crate::CaptureMode::Put(x, y) => ((x, y), (vw, vh), (cw, ch)),
+                    crate::CaptureMode::Fill => ((0, 0), (cw, ch), (cw, ch)),
+                    crate::CaptureMode::Adjust => ((0, 0), (vw, vh), (vw, vh)),
+                    crate::CaptureMode::Pinhole => {
+                        // cover the capture area with the scaled frame matching centers
+                        let scale = (cw as f64 / vw as f64).max(ch as f64 / vh as f64);
+                        let (dw, dh) = if scale.is_normal() {
+                            ((vw as f64 * scale).round() as u32, (vh as f64 * scale).round() as u32)
+                        } else {
+                            (0, 0)
+                        };
+                        let (x, y) = ((cw as i32 - dw as i32) / 2, (ch as i32 - dh as i32) / 2);
+                        ((x, y), (dw, dh), (cw, ch))
+                    }
+                };
+
+                // the canvas doubles as the scratch surface for the whole video frame
+                let frame = self.read_frame(source);
+
+                self.set_capture_size(cw, ch);
+                if dw > 0 && dh > 0 {
+                    let mut target = vec![0; (dw * dh * 4) as usize];
+                    render(&frame, (sw, sh), &mut target, (dw, dh));
+                    self.put_data(&target, (dw, dh), (x, y));
+                }
+
+                (cw, ch)
             }
         }
 
         impl_capture_from_canvas!("2d", $name, $canvas, $context, $options);
         impl_canvas_capture_area!($name);
 
-        impl BrowserVideoCapture for $name {
-            fn capture(
+        #[cfg(feature = "blob")]
+        impl $name {
+            /// Encode the captured frame with the browser image encoder.
+            pub async fn encode_blob(
+                &self,
+                options: crate::BlobOptions,
+            ) -> Result<web_sys::Blob, js_sys::Error> {
+                crate::blob::canvas_blob(&self.canvas, options).await
+            }
+
+            /// Encode the captured frame with the browser image encoder into bytes.
+            pub async fn encode_bytes(&self, options: crate::BlobOptions) -> Result<Vec<u8>, js_sys::Error> {
+                crate::blob_bytes(&self.encode_blob(options).await?).await
+            }
+        }
+
+        #[cfg(feature = "webcodecs")]
+        impl $name {
+            /// Wrap the captured frame into the video frame with `timestamp` in seconds.
+            pub fn video_frame(&self, timestamp: f64) -> Result<web_sys::VideoFrame, js_sys::Error> {
+                crate::webcodecs::canvas_frame(&self.canvas, timestamp)
+            }
+        }
+
+        impl $name {
+            /// Draw the video frame at the place given by `mode`.
+            fn draw_frame(
                 &self,
                 source: &web_sys::HtmlVideoElement,
                 mode: crate::CaptureMode,
@@ -49,7 +219,26 @@ macro_rules! impl_capture_2d {
                     return (cw, ch);
                 }
 
-                self.context.set_image_smoothing_enabled(false);
+                if let Some(undistort) = *self.undistort.borrow() {
+                    return self.render_frame(source, (sw, sh), mode, |frame, size, target, target_size| {
+                        let mut remap = self.remap.borrow_mut();
+                        let table = match remap.take() {
+                            Some(table) if table.source_size() == size && table.target_size() == target_size => table,
+                            _ => crate::RemapTable::new(&undistort, size, target_size),
+                        };
+                        table.apply(frame, target, self.resample.get());
+                        *remap = Some(table);
+                    });
+                }
+
+                if mode == crate::CaptureMode::Fill && (sw, sh) != (cw, ch) && self.resample_on_cpu() {
+                    let resample = self.resample.get();
+                    return self.render_frame(source, (sw, sh), mode, |frame, size, target, target_size| {
+                        crate::utils::remap_rgba(frame, size, target, target_size, resample, |u, v| Some((u, v)));
+                    });
+                }
+
+                self.set_smoothing();
 
                 match mode {
                     crate::CaptureMode::Put(dx, dy) => {
@@ -93,29 +282,247 @@ macro_rules! impl_capture_2d {
                 (cw, ch)
             }
 
-            fn retrieve(&self, buffer: &mut [u8]) {
-                let (w, h) = self.capture_size();
-                if w > 0 && h > 0 {
-                    let data = self.read_data(0, 0, w, h);
-                    buffer.copy_from_slice(data.as_slice());
+            /// Blend the captured frame with the history of the temporal filter.
+            fn filter_temporal(&self, (width, height): (u32, u32)) {
+                let mut temporal = self.temporal.borrow_mut();
+                let Some(filter) = temporal.as_mut() else {
+                    return;
+                };
+                if width == 0 || height == 0 {
+                    return;
                 }
+
+                let mut frame = self.read_data(0, 0, width, height);
+                filter.apply(&mut frame, (width, height));
+
+                let image_data = web_sys::ImageData::new_with_u8_clamped_array_and_sh(
+                    Clamped(frame.as_slice()),
+                    width,
+                    height,
+                )
+                .unwrap();
+                self.context.put_image_data(&image_data, 0 as ImageDataCoord, 0 as ImageDataCoord).unwrap();
             }
+        }
+
+        impl crate::sealed::Sealed for $name {}
 
-            fn data(&self) -> Vec<u8> {
+        impl BrowserVideoCapture for $name {
+            fn capture(
+                &self,
+                source: &web_sys::HtmlVideoElement,
+                mode: crate::CaptureMode,
+            ) -> (u32, u32) {
+                let size = self.draw_frame(source, mode);
+                self.filter_temporal(size);
+                size
+            }
+
+            fn capture_warp(
+                &self,
+                source: &web_sys::HtmlVideoElement,
+                warp: &crate::Warp,
+                mode: crate::CaptureMode,
+            ) -> (u32, u32) {
+                let (sw, sh) = crate::utils::video_size(source);
+
+                if sw == 0 || sh == 0 {
+                    return self.capture_size();
+                }
+
+                let Some(homography) = warp.homography(sw, sh) else {
+                    return self.capture_size();
+                };
+
+                let undistort = *self.undistort.borrow();
+                let resample = self.resample.get();
+                let size = self.render_frame(source, warp.size(), mode, |frame, size, target, target_size| {
+                    crate::utils::remap_rgba(frame, size, target, target_size, resample, |x, y| {
+                        let (u, v) = homography.map(x, y)?;
+                        Some(match undistort {
+                            Some(undistort) => {
+                                let (u, v) = undistort.distort(u * sw as f64, v * sh as f64);
+                                (u / sw as f64, v / sh as f64)
+                            }
+                            None => (u, v),
+                        })
+                    })
+                });
+                self.filter_temporal(size);
+                size
+            }
+
+            fn capture_pyramid(
+                &self,
+                source: &web_sys::HtmlVideoElement,
+                pyramid: &crate::Pyramid,
+            ) -> (u32, u32) {
+                let (sw, sh) = crate::utils::video_size(source);
+                let (cw, ch) = pyramid.size();
+
+                if sw == 0 || sh == 0 {
+                    return self.capture_size();
+                }
+
+                if self.resample_on_cpu() {
+                    let frame = self.read_frame(source);
+                    self.set_capture_size(cw, ch);
+
+                    let mut atlas = vec![0; (cw * ch * 4) as usize];
+                    for level in pyramid.levels() {
+                        let mut target = vec![0; level.area() as usize * 4];
+                        let size = (level.width, level.height);
+                        crate::utils::remap_rgba(&frame, (sw, sh), &mut target, size, self.resample.get(), |u, v| Some((u, v)));
+                        paste(&mut atlas, cw, &target, (level.x as i32, level.y as i32, level.width, level.height), *level);
+                    }
+                    self.put_data(&atlas, (cw, ch), (0, 0));
+                    return (cw, ch);
+                }
+
+                if self.capture_size() != (cw, ch) {
+                    self.set_capture_size(cw, ch);
+                }
+                self.clear();
+                self.set_smoothing();
+
+                for level in pyramid.levels() {
+                    self.context
+                        .draw_image_with_html_video_element_and_dw_and_dh(
+                            source,
+                            level.x as f64,
+                            level.y as f64,
+                            level.width as f64,
+                            level.height as f64,
+                        )
+                        .unwrap();
+                }
+
+                (cw, ch)
+            }
+
+            fn capture_mosaic(
+                &self,
+                sources: &[(&web_sys::HtmlVideoElement, crate::CaptureMode)],
+                mosaic: &crate::Mosaic,
+            ) -> Vec<crate::Rect> {
+                let size = mosaic.size();
+
+                if self.resample_on_cpu() {
+                    // every source needs the whole canvas as the scratch surface
+                    // before the tiles are composed
+                    let frames: Vec<_> = sources
+                        .iter()
+                        .take(mosaic.len())
+                        .map(|(source, _)| {
+                            let (sw, sh) = crate::utils::video_size(source);
+                            (sw > 0 && sh > 0).then(|| ((sw, sh), self.read_frame(source)))
+                        })
+                        .collect();
+                    self.set_capture_size(size.0, size.1);
+
+                    let mut atlas = vec![0; (size.0 * size.1 * 4) as usize];
+                    let rects = frames
+                        .iter()
+                        .zip(sources)
+                        .enumerate()
+                        .map(|(index, (frame, (_, mode)))| {
+                            let Some((source_size, frame)) = frame else {
+                                return Default::default();
+                            };
+                            match mosaic.placement(index, *source_size, *mode) {
+                                Some((placed, rect)) if rect.area() > 0 => {
+                                    let (_, _, w, h) = placed;
+                                    let mut target = vec![0; (w * h * 4) as usize];
+                                    crate::utils::remap_rgba(frame, *source_size, &mut target, (w, h), self.resample.get(), |u, v| Some((u, v)));
+                                    paste(&mut atlas, size.0, &target, placed, rect);
+                                    rect
+                                }
+                                _ => Default::default(),
+                            }
+                        })
+                        .collect();
+                    self.put_data(&atlas, size, (0, 0));
+                    return rects;
+                }
+
+                if self.capture_size() != size {
+                    self.set_capture_size(size.0, size.1);
+                }
+                self.clear();
+                self.set_smoothing();
+
+                sources
+                    .iter()
+                    .enumerate()
+                    .take(mosaic.len())
+                    .map(|(index, (source, mode))| {
+                        let (sw, sh) = crate::utils::video_size(source);
+
+                        match mosaic.placement(index, (sw, sh), *mode) {
+                            Some(((x, y, w, h), rect)) if sw > 0 && sh > 0 && rect.area() > 0 => {
+                                self.context.save();
+                                self.context.begin_path();
+                                self.context.rect(
+                                    rect.x as f64,
+                                    rect.y as f64,
+                                    rect.width as f64,
+                                    rect.height as f64,
+                                );
+                                self.context.clip();
+                                self.context
+                                    .draw_image_with_html_video_element_and_dw_and_dh(
+                                        source, x as f64, y as f64, w as f64, h as f64,
+                                    )
+                                    .unwrap();
+                                self.context.restore();
+                                rect
+                            }
+                            _ => Default::default(),
+                        }
+                    })
+                    .collect()
+            }
+
+            fn resample(&self) -> crate::Resample {
+                self.resample.get()
+            }
+
+            fn set_resample(&self, resample: crate::Resample) {
+                self.resample.set(resample);
+            }
+
+            fn set_undistort(&self, undistort: Option<crate::Undistort>) {
+                self.remap.replace(None);
+                self.undistort.replace(undistort);
+            }
+
+            fn temporal(&self) -> Option<crate::TemporalMode> {
+                self.temporal.borrow().as_ref().map(|filter| filter.mode())
+            }
+
+            fn set_temporal(&self, mode: Option<crate::TemporalMode>) {
+                self.temporal.replace(mode.map(crate::TemporalFilter::new));
+            }
+
+            fn layout(&self) -> crate::FrameLayout {
+                self.layout.get()
+            }
+
+            fn set_layout(&self, layout: crate::FrameLayout) {
+                self.layout.set(layout);
+            }
+
+            fn retrieve(&self, buffer: &mut [u8]) {
                 let (w, h) = self.capture_size();
                 if w > 0 && h > 0 {
-                    self.read_data(0, 0, w, h)
-                } else {
-                    Vec::new()
+                    self.read_into(0, 0, w, h, &mut buffer[..(w * h * 4) as usize]);
+                    self.layout.get().apply(buffer, (w, h), 4);
                 }
             }
 
-            fn read(&self, source: &web_sys::HtmlVideoElement, mode: crate::CaptureMode) -> Vec<u8> {
-                let (w, h) = self.capture(source, mode);
-                if w > 0 && h > 0 {
-                    self.read_data(0, 0, w, h)
-                } else {
-                    Vec::new()
+            fn retrieve_rect(&self, x: i32, y: i32, width: u32, height: u32, buffer: &mut [u8]) {
+                if width > 0 && height > 0 {
+                    self.read_into(x, y, width, height, &mut buffer[..(width * height * 4) as usize]);
                 }
             }
 
@@ -179,16 +586,15 @@ pub mod html {
         HtmlContextOptions2D
     );
 
+    #[cfg(feature = "stream")]
+    impl_canvas_stream!(HtmlCapture2D);
+
     impl HtmlCapture2D {
         pub fn from_context(
             context: web_sys::CanvasRenderingContext2d,
             color: crate::CaptureColor,
         ) -> Option<Self> {
-            context.canvas().map(|canvas| Self {
-                context,
-                canvas,
-                color,
-            })
+            context.canvas().map(|canvas| Self::new(canvas, context, color))
         }
     }
 }
@@ -238,11 +644,7 @@ pub mod offscreen {
             color: crate::CaptureColor,
         ) -> Self {
             let canvas = context.canvas();
-            Self {
-                context,
-                canvas,
-                color,
-            }
+            Self::new(canvas, context, color)
         }
     }
 }
@@ -0,0 +1,99 @@
+use image::{ColorType, ExtendedColorType, ImageEncoder, ImageError, ImageResult};
+
+/// Check that the buffer holds the whole frame of the color type.
+fn check(buffer: &[u8], (width, height): (u32, u32), color: ColorType) -> ImageResult<()> {
+    let expected = width as u64 * height as u64 * color.bytes_per_pixel() as u64;
+
+    if buffer.len() as u64 == expected {
+        Ok(())
+    } else {
+        Err(ImageError::Parameter(
+            image::error::ParameterError::from_kind(
+                image::error::ParameterErrorKind::DimensionMismatch,
+            ),
+        ))
+    }
+}
+
+fn write(
+    encoder: impl ImageEncoder,
+    buffer: &[u8],
+    (width, height): (u32, u32),
+    color: ColorType,
+) -> ImageResult<()> {
+    check(buffer, (width, height), color)?;
+    encoder.write_image(buffer, width, height, ExtendedColorType::from(color))
+}
+
+/// Encode the raw frame into PNG.
+#[cfg(feature = "png")]
+pub fn encode_png(buffer: &[u8], size: (u32, u32), color: ColorType) -> ImageResult<Vec<u8>> {
+    let mut output = Vec::new();
+    write(
+        image::codecs::png::PngEncoder::new(&mut output),
+        buffer,
+        size,
+        color,
+    )?;
+    Ok(output)
+}
+
+/// Encode the raw frame into JPEG with `quality` from `1` to `100`.
+///
+/// JPEG has no alpha channel, so it is dropped from the frame.
+#[cfg(feature = "jpeg")]
+pub fn encode_jpeg(
+    buffer: &[u8],
+    size: (u32, u32),
+    color: ColorType,
+    quality: u8,
+) -> ImageResult<Vec<u8>> {
+    let (opaque, color) = match color {
+        ColorType::La8 => (strip_alpha(buffer, 2), ColorType::L8),
+        ColorType::Rgba8 => (strip_alpha(buffer, 4), ColorType::Rgb8),
+        _ => (buffer.to_vec(), color),
+    };
+
+    let mut output = Vec::new();
+    write(
+        image::codecs::jpeg::JpegEncoder::new_with_quality(&mut output, quality),
+        &opaque,
+        size,
+        color,
+    )?;
+    Ok(output)
+}
+
+#[cfg(feature = "jpeg")]
+fn strip_alpha(buffer: &[u8], channels: usize) -> Vec<u8> {
+    buffer
+        .chunks_exact(channels)
+        .flat_map(|pixel| pixel[..channels - 1].iter().copied())
+        .collect()
+}
+
+/// Encode the raw frame into lossless WebP.
+#[cfg(feature = "webp")]
+pub fn encode_webp(buffer: &[u8], size: (u32, u32), color: ColorType) -> ImageResult<Vec<u8>> {
+    let mut output = Vec::new();
+    write(
+        image::codecs::webp::WebPEncoder::new_lossless(&mut output),
+        buffer,
+        size,
+        color,
+    )?;
+    Ok(output)
+}
+
+/// Encode the raw frame into BMP.
+#[cfg(feature = "bmp")]
+pub fn encode_bmp(buffer: &[u8], size: (u32, u32), color: ColorType) -> ImageResult<Vec<u8>> {
+    let mut output = Vec::new();
+    write(
+        image::codecs::bmp::BmpEncoder::new(&mut output),
+        buffer,
+        size,
+        color,
+    )?;
+    Ok(output)
+}
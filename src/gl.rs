@@ -1,9 +1,13 @@
-use std::fmt::Display;
+use std::{
+    cell::{Cell, OnceCell, RefCell},
+    fmt::Display,
+};
 
 use web_sys::{
     js_sys::{self, Float32Array, Uint16Array},
     wasm_bindgen::{JsCast, JsValue},
-    WebGlBuffer, WebGlProgram, WebGlShader, WebGlTexture, WebGlUniformLocation,
+    WebGlBuffer, WebGlFramebuffer, WebGlProgram, WebGlShader, WebGlTexture,
+    WebGlUniformLocation,
 };
 
 use crate::{BrowserVideoCapture, CaptureArea};
@@ -38,7 +42,7 @@ macro_rules! initialize {
         $gl.tex_parameteri(
             $context::TEXTURE_2D,
             $context::TEXTURE_MIN_FILTER,
-            $context::LINEAR as i32,
+            $context::NEAREST as i32,
         );
         $gl.tex_parameteri(
             $context::TEXTURE_2D,
@@ -65,14 +69,159 @@ macro_rules! validate {
     };
 }
 
+/// `PACK_ROW_LENGTH` of WebGL2, shared by both context types in the macro.
+const PACK_ROW_LENGTH: u32 = 0x0D02;
+/// `RGBA32F` of WebGL2, shared by both context types in the macro.
+const RGBA32F: u32 = 0x8814;
+/// `RGBA16F` and `HALF_FLOAT` of WebGL2.
+const RGBA16F: u32 = 0x881A;
+const HALF_FLOAT: u32 = 0x140B;
+/// `HALF_FLOAT_OES` of the `OES_texture_half_float` extension of WebGL.
+const HALF_FLOAT_OES: u32 = 0x8D61;
+
+/// Channel weights of the histogram rows in the order of [`crate::StatsChannel`].
+const HISTOGRAM_WEIGHTS: [[f32; 4]; 5] = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+    [0.299, 0.587, 0.114, 0.0],
+];
+
+/// Number of the columns and rows of the cells of the sharpness sums.
+const SHARPNESS_GRID: u32 = 16;
+
+/// Resources shared by the passes which scatter one point per pixel
+/// into the cells of the float target with the additive blending.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ScatterPass {
+    vertex: WebGlShader,
+    fragment: WebGlShader,
+    program: WebGlProgram,
+    target: WebGlTexture,
+    target_size: Cell<(u32, u32)>,
+    framebuffer: WebGlFramebuffer,
+    /// Pixel indices of the points.
+    points: WebGlBuffer,
+    points_count: Cell<u32>,
+    a_index: u32,
+}
+
+/// Histogram pass into the 256x5 target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct StatsPass {
+    scatter: ScatterPass,
+    /// Copy of the captured frame.
+    source: WebGlTexture,
+    u_texture: Option<WebGlUniformLocation>,
+    u_size: Option<WebGlUniformLocation>,
+    u_weights: Option<WebGlUniformLocation>,
+}
+
+/// Focus measure pass summing the per-pixel measure and its square
+/// into the two halves of the target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SharpnessPass {
+    scatter: ScatterPass,
+    /// Copy of the captured frame.
+    source: WebGlTexture,
+    u_texture: Option<WebGlUniformLocation>,
+    u_size: Option<WebGlUniformLocation>,
+    u_rect: Option<WebGlUniformLocation>,
+    u_grid: Option<WebGlUniformLocation>,
+    u_tenengrad: Option<WebGlUniformLocation>,
+    u_square: Option<WebGlUniformLocation>,
+}
+
+/// Frame difference pass into the target of the grid size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MotionPass {
+    scatter: ScatterPass,
+    /// Copies of the current and the previous captured frames.
+    frames: [WebGlTexture; 2],
+    current: Cell<usize>,
+    frame_size: Cell<(u32, u32)>,
+    u_current: Option<WebGlUniformLocation>,
+    u_previous: Option<WebGlUniformLocation>,
+    u_size: Option<WebGlUniformLocation>,
+    u_grid: Option<WebGlUniformLocation>,
+    u_threshold: Option<WebGlUniformLocation>,
+}
+
+/// Background model pass of WebGL2, which renders the running Gaussian
+/// of every pixel into the float texture and its foreground flag into the mask target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BackgroundPass {
+    shaders: [WebGlShader; 3],
+    update: WebGlProgram,
+    mask: WebGlProgram,
+    /// Copy of the captured frame.
+    frame: WebGlTexture,
+    /// The previous and the next models.
+    models: [WebGlTexture; 2],
+    framebuffers: [WebGlFramebuffer; 2],
+    /// Foreground mask target read back instead of the captured frame.
+    mask_target: WebGlTexture,
+    mask_framebuffer: WebGlFramebuffer,
+    current: Cell<usize>,
+    /// Size of the models, zero until the first frame initializes them.
+    size: Cell<(u32, u32)>,
+    u_frame: Option<WebGlUniformLocation>,
+    u_model: Option<WebGlUniformLocation>,
+    u_initialize: Option<WebGlUniformLocation>,
+    u_rate: Option<WebGlUniformLocation>,
+    u_threshold: Option<WebGlUniformLocation>,
+    u_variance: Option<WebGlUniformLocation>,
+    u_mask_model: Option<WebGlUniformLocation>,
+    a_update: u32,
+    a_mask: u32,
+}
+
+/// Temporal filter pass, which blends the captured frame with the history texture
+/// and presents the result in place of the frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TemporalPass {
+    shaders: [WebGlShader; 3],
+    blend: WebGlProgram,
+    present: WebGlProgram,
+    /// Copy of the captured frame.
+    frame: WebGlTexture,
+    /// The previous and the next history.
+    history: [WebGlTexture; 2],
+    framebuffers: [WebGlFramebuffer; 2],
+    /// Internal format and type of the float or half-float history,
+    /// `None` when the context cannot render into them.
+    format: Option<(u32, u32)>,
+    current: Cell<usize>,
+    size: Cell<(u32, u32)>,
+    frames: Cell<u32>,
+    u_frame: Option<WebGlUniformLocation>,
+    u_history: Option<WebGlUniformLocation>,
+    u_weight: Option<WebGlUniformLocation>,
+    u_threshold: Option<WebGlUniformLocation>,
+    u_texture: Option<WebGlUniformLocation>,
+    a_blend: u32,
+    a_present: u32,
+}
+
 macro_rules! impl_capture_gl {
     ($name:tt $canvas:ty, $context:tt, $options:ty, $capture_method:tt, $version:tt) => {
-    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[derive(Debug, Clone)]
     pub struct $name {
         canvas: $canvas,
         context: $context,
         #[allow(dead_code)]
         color: crate::CaptureColor,
+        resample: Cell<crate::Resample>,
+        layout: Cell<crate::FrameLayout>,
+        stats: OnceCell<Option<StatsPass>>,
+        sharpness: OnceCell<Option<SharpnessPass>>,
+        motion: OnceCell<Option<MotionPass>>,
+        background: OnceCell<Option<BackgroundPass>>,
+        temporal: Cell<Option<crate::TemporalMode>>,
+        temporal_pass: OnceCell<Option<TemporalPass>>,
+        /// History on the CPU when the context has no float targets.
+        temporal_cpu: RefCell<Option<crate::TemporalFilter>>,
 
         vertex: Option<WebGlShader>,
         fragment: Option<WebGlShader>,
@@ -83,10 +232,27 @@ macro_rules! impl_capture_gl {
         texture: Option<WebGlTexture>,
 
         u_texture: Option<WebGlUniformLocation>,
+        u_warp: Option<WebGlUniformLocation>,
+        u_size: Option<WebGlUniformLocation>,
+        u_undistort: Option<WebGlUniformLocation>,
+        u_camera: Option<WebGlUniformLocation>,
+        u_radial: Option<WebGlUniformLocation>,
+        u_tangential: Option<WebGlUniformLocation>,
+        u_resample: Option<WebGlUniformLocation>,
+        u_footprint: Option<WebGlUniformLocation>,
         #[allow(dead_code)]
         a_tex_coord: Option<u32>,
     }
 
+    /// Captures are the same when they draw into the same context.
+    impl PartialEq for $name {
+        fn eq(&self, other: &Self) -> bool {
+            self.canvas == other.canvas && self.context == other.context
+        }
+    }
+
+    impl Eq for $name {}
+
     impl $name {
         fn program(&self) -> Option<&WebGlProgram> {
             self.program.as_ref()
@@ -108,6 +274,14 @@ macro_rules! impl_capture_gl {
             self.u_texture.as_ref()
         }
 
+        fn u_warp(&self) -> Option<&WebGlUniformLocation> {
+            self.u_warp.as_ref()
+        }
+
+        fn u_size(&self) -> Option<&WebGlUniformLocation> {
+            self.u_size.as_ref()
+        }
+
         pub fn new(
             canvas: $canvas,
             context: $context,
@@ -122,9 +296,9 @@ macro_rules! impl_capture_gl {
                 context,
                 $context::FRAGMENT_SHADER,
                 match color {
-                    crate::CaptureColor::RGBL => include_str!("glsl/rgbl.frag"),
-                    crate::CaptureColor::LLLA => include_str!("glsl/llla.frag"),
-                    crate::CaptureColor::RGBA => include_str!("glsl/rgba.frag"),
+                    crate::CaptureColor::RGBL => concat!(include_str!("glsl/source.frag"), include_str!("glsl/rgbl.frag")),
+                    crate::CaptureColor::LLLA => concat!(include_str!("glsl/source.frag"), include_str!("glsl/llla.frag")),
+                    crate::CaptureColor::RGBA => concat!(include_str!("glsl/source.frag"), include_str!("glsl/rgba.frag")),
                 }
             );
             let program = vertex
@@ -137,9 +311,25 @@ macro_rules! impl_capture_gl {
             let indices = context.create_buffer();
 
             let mut u_texture = None;
+            let mut u_warp = None;
+            let mut u_size = None;
+            let mut u_undistort = None;
+            let mut u_camera = None;
+            let mut u_radial = None;
+            let mut u_tangential = None;
+            let mut u_resample = None;
+            let mut u_footprint = None;
             let mut a_tex_coord = None;
             if let Some(program) = program.as_ref() {
                 u_texture = context.get_uniform_location(&program, "u_texture");
+                u_warp = context.get_uniform_location(&program, "u_warp");
+                u_size = context.get_uniform_location(&program, "u_size");
+                u_undistort = context.get_uniform_location(&program, "u_undistort");
+                u_camera = context.get_uniform_location(&program, "u_camera");
+                u_radial = context.get_uniform_location(&program, "u_radial");
+                u_tangential = context.get_uniform_location(&program, "u_tangential");
+                u_resample = context.get_uniform_location(&program, "u_resample");
+                u_footprint = context.get_uniform_location(&program, "u_footprint");
                 a_tex_coord = Some(context.get_attrib_location(&program, "a_texCoord"))
                     .filter(|v| *v != -1)
                     .map(|v| v as u32);
@@ -158,7 +348,7 @@ macro_rules! impl_capture_gl {
                 context.vertex_attrib_pointer_with_i32(
                     a_tex_coord,
                     2,
-                    WebGlRenderingContext::FLOAT,
+                    $context::FLOAT,
                     false,
                     0,
                     0,
@@ -191,6 +381,15 @@ macro_rules! impl_capture_gl {
                 canvas,
                 context,
                 color,
+                resample: Cell::new(Default::default()),
+                layout: Cell::new(Default::default()),
+                stats: OnceCell::new(),
+                sharpness: OnceCell::new(),
+                motion: OnceCell::new(),
+                background: OnceCell::new(),
+                temporal: Cell::new(None),
+                temporal_pass: OnceCell::new(),
+                temporal_cpu: RefCell::new(None),
                 vertex,
                 fragment,
                 program,
@@ -198,6 +397,14 @@ macro_rules! impl_capture_gl {
                 coords,
                 indices,
                 u_texture,
+                u_warp,
+                u_size,
+                u_undistort,
+                u_camera,
+                u_radial,
+                u_tangential,
+                u_resample,
+                u_footprint,
                 a_tex_coord,
             }
         }
@@ -220,6 +427,14 @@ macro_rules! impl_capture_gl {
                 && self.coords.is_some()
                 && self.indices.is_some()
                 && self.u_texture.is_some()
+                && self.u_warp.is_some()
+                && self.u_size.is_some()
+                && self.u_undistort.is_some()
+                && self.u_camera.is_some()
+                && self.u_radial.is_some()
+                && self.u_tangential.is_some()
+                && self.u_resample.is_some()
+                && self.u_footprint.is_some()
                 && self.a_tex_coord.is_some())
             .then_some(())
             .ok_or(None)?;
@@ -248,19 +463,38 @@ macro_rules! impl_capture_gl {
     );
     impl_canvas_capture_area!($name);
 
-    impl BrowserVideoCapture for $name {
-        fn capture(
+    #[cfg(feature = "blob")]
+    impl $name {
+        /// Encode the captured frame with the browser image encoder.
+        ///
+        /// WebGL captures keep the frame only until it is presented,
+        /// so either encode right after the capture or create the context
+        /// with `preserve_drawing_buffer` enabled.
+        pub async fn encode_blob(
             &self,
-            source: &web_sys::HtmlVideoElement,
-            mode: crate::CaptureMode,
-        ) -> (u32, u32) {
-            let (sw, sh) = crate::utils::video_size(source);
-            let (mut cw, mut ch) = self.capture_size();
+            options: crate::BlobOptions,
+        ) -> Result<web_sys::Blob, js_sys::Error> {
+            crate::blob::canvas_blob(&self.canvas, options).await
+        }
 
-            if sw == 0 || sh == 0 {
-                return (cw, ch);
-            }
+        /// Encode the captured frame with the browser image encoder into bytes.
+        pub async fn encode_bytes(&self, options: crate::BlobOptions) -> Result<Vec<u8>, js_sys::Error> {
+            crate::blob_bytes(&self.encode_blob(options).await?).await
+        }
+    }
+
+    #[cfg(feature = "webcodecs")]
+    impl $name {
+        /// Wrap the captured frame into the video frame with `timestamp` in seconds.
+        ///
+        /// Same as for [`encode_blob`](Self::encode_blob), wrap it right after the capture.
+        pub fn video_frame(&self, timestamp: f64) -> Result<web_sys::VideoFrame, js_sys::Error> {
+            crate::webcodecs::canvas_frame(&self.canvas, timestamp)
+        }
+    }
 
+    impl $name {
+        fn bind(&self) {
             self.context.use_program(self.program());
             self.context
                 .bind_buffer($context::ARRAY_BUFFER, self.coords());
@@ -276,14 +510,830 @@ macro_rules! impl_capture_gl {
             self.context.vertex_attrib_pointer_with_i32(
                     self.a_tex_coord.unwrap(),
                     2,
-                    WebGlRenderingContext::FLOAT,
+                    $context::FLOAT,
                     false,
                     0,
                     0,
                 );
             self.context.enable_vertex_attrib_array(self.a_tex_coord.unwrap());
+        }
+
+        fn unbind(&self) {
+            self.context.use_program(None);
+            self.context
+                .bind_texture($context::TEXTURE_2D, None);
+            self.context
+                .bind_buffer($context::ELEMENT_ARRAY_BUFFER, None);
+            self.context
+                .bind_buffer($context::ARRAY_BUFFER, None);
+        }
+
+        fn filter(
+            &self,
+            source: &web_sys::HtmlVideoElement,
+            warp: &crate::Homography,
+            (width, height): (u32, u32),
+        ) -> crate::utils::Filter {
+            crate::utils::Filter::new(
+                self.resample.get(),
+                crate::utils::video_size(source),
+                (width, height),
+                |x, y| warp.map(x, y),
+            )
+        }
+
+        /// Check if the filter needs mipmaps for the large reduction,
+        /// mipmaps of non power of two textures are available only in WebGL2.
+        fn mipmap(&self, filter: &crate::utils::Filter) -> bool {
+            $version == "webgl2"
+                && filter.resample != crate::Resample::Nearest
+                && (filter.footprint.0 > 2.0 || filter.footprint.1 > 2.0)
+        }
+
+        /// Upload the video frame into the texture.
+        fn upload(&self, source: &web_sys::HtmlVideoElement, mipmap: bool) {
+            let mag_filter = match self.resample.get() {
+                crate::Resample::Nearest => $context::NEAREST,
+                _ => $context::LINEAR,
+            };
+            let min_filter = if mipmap {
+                $context::LINEAR_MIPMAP_LINEAR
+            } else {
+                mag_filter
+            };
+            self.context.tex_parameteri(
+                $context::TEXTURE_2D,
+                $context::TEXTURE_MAG_FILTER,
+                mag_filter as i32,
+            );
+            self.context.tex_parameteri(
+                $context::TEXTURE_2D,
+                $context::TEXTURE_MIN_FILTER,
+                min_filter as i32,
+            );
+
+            self.context
+                .$capture_method(
+                    $context::TEXTURE_2D,
+                    0,
+                    $context::RGBA as i32,
+                    $context::RGBA,
+                    $context::UNSIGNED_BYTE,
+                    source,
+                )
+                .unwrap();
+
+            if mipmap {
+                self.context.generate_mipmap($context::TEXTURE_2D);
+            }
+        }
+
+        /// Create the scatter pass, `None` when the context cannot blend into
+        /// float targets or fetch textures in the vertex shader.
+        fn scatter_pass(&self, vertex: &str, fragment: &str) -> Option<ScatterPass> {
+            let gl = &self.context;
+            let extensions: &[&str] = if $version == "webgl2" {
+                &["EXT_color_buffer_float", "EXT_float_blend"]
+            } else {
+                &["OES_texture_float", "WEBGL_color_buffer_float", "EXT_float_blend"]
+            };
+            for name in extensions {
+                gl.get_extension(name).ok()??;
+            }
+            gl.get_parameter($context::MAX_VERTEX_TEXTURE_IMAGE_UNITS)
+                .ok()?
+                .as_f64()
+                .filter(|units| *units > 0.0)?;
+
+            let pass = ScatterPass {
+                vertex: initialize!(shader gl, $context::VERTEX_SHADER, vertex)?,
+                fragment: initialize!(shader gl, $context::FRAGMENT_SHADER, fragment)?,
+                program: gl.create_program()?,
+                target: gl.create_texture()?,
+                target_size: Cell::new((0, 0)),
+                framebuffer: gl.create_framebuffer()?,
+                points: gl.create_buffer()?,
+                points_count: Cell::new(0),
+                a_index: 0,
+            };
+            gl.attach_shader(&pass.program, &pass.vertex);
+            gl.attach_shader(&pass.program, &pass.fragment);
+            gl.link_program(&pass.program);
+
+            let a_index = Some(gl.get_attrib_location(&pass.program, "a_index"))
+                .filter(|v| *v != -1)
+                .map(|v| v as u32);
+            let valid = validate!($context shader gl, &pass.vertex).is_ok()
+                && validate!($context program gl, &pass.program).is_ok();
+
+            match a_index.filter(|_| valid) {
+                Some(a_index) => Some(ScatterPass { a_index, ..pass }),
+                None => {
+                    self.delete_scatter_pass(&pass);
+                    None
+                }
+            }
+        }
+
+        fn delete_scatter_pass(&self, pass: &ScatterPass) {
+            let gl = &self.context;
+            gl.delete_framebuffer(Some(&pass.framebuffer));
+            gl.delete_texture(Some(&pass.target));
+            gl.delete_buffer(Some(&pass.points));
+            gl.delete_program(Some(&pass.program));
+            gl.delete_shader(Some(&pass.vertex));
+            gl.delete_shader(Some(&pass.fragment));
+        }
+
+        /// Allocate the float target of `size` cells, `false` when it cannot be rendered into.
+        fn resize_scatter_target(&self, pass: &ScatterPass, (width, height): (u32, u32)) -> bool {
+            if pass.target_size.get() == (width, height) {
+                return true;
+            }
+
+            let gl = &self.context;
+            gl.bind_texture($context::TEXTURE_2D, Some(&pass.target));
+            initialize!($context texture gl);
+            let format = if $version == "webgl2" { RGBA32F } else { $context::RGBA };
+            let result = gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+                $context::TEXTURE_2D,
+                0,
+                format as i32,
+                width as i32,
+                height as i32,
+                0,
+                $context::RGBA,
+                $context::FLOAT,
+                None,
+            );
+            gl.bind_texture($context::TEXTURE_2D, None);
+
+            gl.bind_framebuffer($context::FRAMEBUFFER, Some(&pass.framebuffer));
+            gl.framebuffer_texture_2d(
+                $context::FRAMEBUFFER,
+                $context::COLOR_ATTACHMENT0,
+                $context::TEXTURE_2D,
+                Some(&pass.target),
+                0,
+            );
+            let complete = gl.check_framebuffer_status($context::FRAMEBUFFER)
+                == $context::FRAMEBUFFER_COMPLETE;
+            gl.bind_framebuffer($context::FRAMEBUFFER, None);
+
+            let valid = result.is_ok() && complete;
+            pass.target_size.set(if valid { (width, height) } else { (0, 0) });
+            valid
+        }
+
+        /// Copy the captured frame into the texture,
+        /// since the default framebuffer cannot be sampled.
+        fn copy_frame(&self, texture: &WebGlTexture, (width, height): (u32, u32)) {
+            let gl = &self.context;
+            gl.bind_texture($context::TEXTURE_2D, Some(texture));
+            initialize!($context texture gl);
+            gl.copy_tex_image_2d(
+                $context::TEXTURE_2D,
+                0,
+                $context::RGBA,
+                0,
+                0,
+                width as i32,
+                height as i32,
+                0,
+            );
+            gl.bind_texture($context::TEXTURE_2D, None);
+        }
+
+        /// Bind the pass to scatter the pixels of the frame of `size` into the cleared target.
+        fn begin_scatter(&self, pass: &ScatterPass, (width, height): (u32, u32)) {
+            let gl = &self.context;
+            let count = width * height;
+
+            gl.bind_buffer($context::ARRAY_BUFFER, Some(&pass.points));
+            if pass.points_count.get() != count {
+                let indices: Vec<f32> = (0..count).map(|index| index as f32).collect();
+                gl.buffer_data_with_array_buffer_view(
+                    $context::ARRAY_BUFFER,
+                    &Float32Array::from(indices.as_slice()),
+                    $context::STATIC_DRAW,
+                );
+                pass.points_count.set(count);
+            }
+            gl.vertex_attrib_pointer_with_i32(pass.a_index, 1, $context::FLOAT, false, 0, 0);
+            gl.enable_vertex_attrib_array(pass.a_index);
+            gl.use_program(Some(&pass.program));
+
+            gl.bind_framebuffer($context::FRAMEBUFFER, Some(&pass.framebuffer));
+            gl.clear_color(0.0, 0.0, 0.0, 0.0);
+            gl.clear($context::COLOR_BUFFER_BIT);
+            gl.enable($context::BLEND);
+            gl.blend_func($context::ONE, $context::ONE);
+        }
+
+        /// Read the first channel of the target back and restore the capture state.
+        fn end_scatter(&self, pass: &ScatterPass) -> Option<Vec<f32>> {
+            let gl = &self.context;
+            let (width, height) = pass.target_size.get();
+            gl.disable($context::BLEND);
+
+            let cells = Float32Array::new_with_length(width * height * 4);
+            let result = gl.read_pixels_with_opt_array_buffer_view(
+                0,
+                0,
+                width as i32,
+                height as i32,
+                $context::RGBA,
+                $context::FLOAT,
+                Some(&cells),
+            );
+
+            let (cw, ch) = self.capture_size();
+            gl.bind_framebuffer($context::FRAMEBUFFER, None);
+            gl.viewport(0, 0, cw as i32, ch as i32);
+            gl.disable_vertex_attrib_array(pass.a_index);
+            gl.bind_buffer($context::ARRAY_BUFFER, None);
+            gl.bind_texture($context::TEXTURE_2D, None);
+            gl.use_program(None);
+
+            if result.is_err() || gl.get_error() != $context::NO_ERROR {
+                return None;
+            }
+            Some(cells.to_vec().chunks_exact(4).map(|cell| cell[0]).collect())
+        }
+
+        fn stats_pass(&self) -> Option<StatsPass> {
+            let scatter = self.scatter_pass(
+                include_str!("glsl/histogram.vert"),
+                include_str!("glsl/histogram.frag"),
+            )?;
+            let gl = &self.context;
+            let source = match gl.create_texture() {
+                Some(source) => source,
+                None => {
+                    self.delete_scatter_pass(&scatter);
+                    return None;
+                }
+            };
+
+            Some(StatsPass {
+                u_texture: gl.get_uniform_location(&scatter.program, "u_texture"),
+                u_size: gl.get_uniform_location(&scatter.program, "u_size"),
+                u_weights: gl.get_uniform_location(&scatter.program, "u_weights"),
+                scatter,
+                source,
+            })
+        }
+
+        /// Compute the histograms of the captured frame on the GPU.
+        fn gpu_stats(&self) -> Option<crate::FrameStats> {
+            let pass = self.stats.get_or_init(|| self.stats_pass()).as_ref()?;
+            let (width, height) = self.capture_size();
+            if width * height == 0 || !self.resize_scatter_target(&pass.scatter, (256, 5)) {
+                return None;
+            }
+
+            let gl = &self.context;
+            while gl.get_error() != $context::NO_ERROR {}
+
+            self.copy_frame(&pass.source, (width, height));
+            self.begin_scatter(&pass.scatter, (width, height));
+            gl.active_texture($context::TEXTURE0);
+            gl.bind_texture($context::TEXTURE_2D, Some(&pass.source));
+            gl.uniform1i(pass.u_texture.as_ref(), 0);
+            gl.uniform2f(pass.u_size.as_ref(), width as f32, height as f32);
+            for (row, weights) in HISTOGRAM_WEIGHTS.iter().enumerate() {
+                gl.viewport(0, row as i32, 256, 1);
+                gl.uniform4fv_with_f32_array(pass.u_weights.as_ref(), weights);
+                gl.draw_arrays($context::POINTS, 0, (width * height) as i32);
+            }
+            let bins = self.end_scatter(&pass.scatter)?;
+
+            let mut histograms = [[0; 256]; 5];
+            for (histogram, bins) in histograms.iter_mut().zip(bins.chunks_exact(256)) {
+                for (value, bin) in histogram.iter_mut().zip(bins) {
+                    *value = *bin as u32;
+                }
+            }
+            Some(crate::FrameStats { histograms })
+        }
+
+        fn sharpness_pass(&self) -> Option<SharpnessPass> {
+            let scatter = self.scatter_pass(
+                include_str!("glsl/sharpness.vert"),
+                include_str!("glsl/sharpness.frag"),
+            )?;
+            let gl = &self.context;
+            let source = match gl.create_texture() {
+                Some(source) => source,
+                None => {
+                    self.delete_scatter_pass(&scatter);
+                    return None;
+                }
+            };
+
+            Some(SharpnessPass {
+                u_texture: gl.get_uniform_location(&scatter.program, "u_texture"),
+                u_size: gl.get_uniform_location(&scatter.program, "u_size"),
+                u_rect: gl.get_uniform_location(&scatter.program, "u_rect"),
+                u_grid: gl.get_uniform_location(&scatter.program, "u_grid"),
+                u_tenengrad: gl.get_uniform_location(&scatter.program, "u_tenengrad"),
+                u_square: gl.get_uniform_location(&scatter.program, "u_square"),
+                scatter,
+                source,
+            })
+        }
+
+        /// Score the sharpness of the captured frame inside `rect` on the GPU.
+        fn gpu_sharpness(&self, method: crate::SharpnessMethod, rect: Option<crate::Rect>) -> Option<f64> {
+            let pass = self.sharpness.get_or_init(|| self.sharpness_pass()).as_ref()?;
+            let (width, height) = self.capture_size();
+            let frame = crate::Rect::new(0, 0, width, height);
+            let rect = match rect {
+                Some(rect) => frame.clip(rect.x as i32, rect.y as i32, rect.width, rect.height),
+                None => frame,
+            };
+            if rect.area() == 0 {
+                return Some(0.0);
+            }
+            let grid = SHARPNESS_GRID;
+            if !self.resize_scatter_target(&pass.scatter, (2 * grid, grid)) {
+                return None;
+            }
+
+            let gl = &self.context;
+            while gl.get_error() != $context::NO_ERROR {}
+
+            let tenengrad = method == crate::SharpnessMethod::Tenengrad;
+            self.copy_frame(&pass.source, (width, height));
+            self.begin_scatter(&pass.scatter, rect.size());
+            gl.active_texture($context::TEXTURE0);
+            gl.bind_texture($context::TEXTURE_2D, Some(&pass.source));
+            gl.uniform1i(pass.u_texture.as_ref(), 0);
+            gl.uniform2f(pass.u_size.as_ref(), width as f32, height as f32);
+            gl.uniform4f(
+                pass.u_rect.as_ref(),
+                rect.x as f32,
+                rect.y as f32,
+                rect.width as f32,
+                rect.height as f32,
+            );
+            gl.uniform2f(pass.u_grid.as_ref(), grid as f32, grid as f32);
+            gl.uniform1f(pass.u_tenengrad.as_ref(), tenengrad as i32 as f32);
+            // the left half sums the measure and the right half sums its square,
+            // the Tenengrad measure is already squared
+            for square in [false, true] {
+                if tenengrad && !square {
+                    continue;
+                }
+                gl.viewport(if square { grid as i32 } else { 0 }, 0, grid as i32, grid as i32);
+                gl.uniform1f(pass.u_square.as_ref(), square as i32 as f32);
+                gl.draw_arrays($context::POINTS, 0, rect.area() as i32);
+            }
+            let cells = self.end_scatter(&pass.scatter)?;
+
+            let (mut sum, mut squares) = (0.0, 0.0);
+            for row in cells.chunks_exact(2 * grid as usize) {
+                let (left, right) = row.split_at(grid as usize);
+                sum += left.iter().map(|&cell| cell as f64).sum::<f64>();
+                squares += right.iter().map(|&cell| cell as f64).sum::<f64>();
+            }
+            Some(method.finish(sum, squares, rect.area()))
+        }
+
+        /// Create the background pass, `None` for WebGL or without float targets.
+        fn background_pass(&self) -> Option<BackgroundPass> {
+            let gl = &self.context;
+            if $version != "webgl2" {
+                return None;
+            }
+            gl.get_extension("EXT_color_buffer_float").ok()??;
+
+            let shaders = [
+                initialize!(shader gl, $context::VERTEX_SHADER, include_str!("glsl/quad.vert"))?,
+                initialize!(shader gl, $context::FRAGMENT_SHADER, include_str!("glsl/background.frag"))?,
+                initialize!(shader gl, $context::FRAGMENT_SHADER, include_str!("glsl/mask.frag"))?,
+            ];
+            let pass = BackgroundPass {
+                update: initialize!(program gl, &shaders[0], &shaders[1])?,
+                mask: initialize!(program gl, &shaders[0], &shaders[2])?,
+                frame: gl.create_texture()?,
+                models: [gl.create_texture()?, gl.create_texture()?],
+                framebuffers: [gl.create_framebuffer()?, gl.create_framebuffer()?],
+                mask_target: gl.create_texture()?,
+                mask_framebuffer: gl.create_framebuffer()?,
+                current: Cell::new(0),
+                size: Cell::new((0, 0)),
+                u_frame: None,
+                u_model: None,
+                u_initialize: None,
+                u_rate: None,
+                u_threshold: None,
+                u_variance: None,
+                u_mask_model: None,
+                a_update: 0,
+                a_mask: 0,
+                shaders,
+            };
+
+            let valid = pass.shaders.iter().all(|shader| validate!($context shader gl, shader).is_ok())
+                && validate!($context program gl, &pass.update).is_ok()
+                && validate!($context program gl, &pass.mask).is_ok();
+            let a_update = gl.get_attrib_location(&pass.update, "a_position");
+            let a_mask = gl.get_attrib_location(&pass.mask, "a_position");
+            if !valid || a_update < 0 || a_mask < 0 {
+                self.delete_background_pass(&pass);
+                return None;
+            }
+
+            Some(BackgroundPass {
+                u_frame: gl.get_uniform_location(&pass.update, "u_frame"),
+                u_model: gl.get_uniform_location(&pass.update, "u_model"),
+                u_initialize: gl.get_uniform_location(&pass.update, "u_initialize"),
+                u_rate: gl.get_uniform_location(&pass.update, "u_rate"),
+                u_threshold: gl.get_uniform_location(&pass.update, "u_threshold"),
+                u_variance: gl.get_uniform_location(&pass.update, "u_variance"),
+                u_mask_model: gl.get_uniform_location(&pass.mask, "u_model"),
+                a_update: a_update as u32,
+                a_mask: a_mask as u32,
+                ..pass
+            })
+        }
+
+        fn delete_background_pass(&self, pass: &BackgroundPass) {
+            let gl = &self.context;
+            pass.framebuffers.iter().for_each(|framebuffer| gl.delete_framebuffer(Some(framebuffer)));
+            pass.models.iter().for_each(|model| gl.delete_texture(Some(model)));
+            gl.delete_framebuffer(Some(&pass.mask_framebuffer));
+            gl.delete_texture(Some(&pass.mask_target));
+            gl.delete_texture(Some(&pass.frame));
+            gl.delete_program(Some(&pass.update));
+            gl.delete_program(Some(&pass.mask));
+            pass.shaders.iter().for_each(|shader| gl.delete_shader(Some(shader)));
+        }
+
+        /// Allocate the float models and the mask of `size`, `false` when they cannot be rendered into.
+        fn resize_background(&self, pass: &BackgroundPass, (width, height): (u32, u32)) -> bool {
+            let gl = &self.context;
+            let mut valid = true;
+
+            for (model, framebuffer) in pass.models.iter().zip(&pass.framebuffers) {
+                gl.bind_texture($context::TEXTURE_2D, Some(model));
+                initialize!($context texture gl);
+                valid &= gl
+                    .tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+                        $context::TEXTURE_2D,
+                        0,
+                        RGBA32F as i32,
+                        width as i32,
+                        height as i32,
+                        0,
+                        $context::RGBA,
+                        $context::FLOAT,
+                        None,
+                    )
+                    .is_ok();
+
+                gl.bind_framebuffer($context::FRAMEBUFFER, Some(framebuffer));
+                gl.framebuffer_texture_2d(
+                    $context::FRAMEBUFFER,
+                    $context::COLOR_ATTACHMENT0,
+                    $context::TEXTURE_2D,
+                    Some(model),
+                    0,
+                );
+                valid &= gl.check_framebuffer_status($context::FRAMEBUFFER)
+                    == $context::FRAMEBUFFER_COMPLETE;
+            }
+
+            gl.bind_texture($context::TEXTURE_2D, Some(&pass.mask_target));
+            initialize!($context texture gl);
+            valid &= gl
+                .tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+                    $context::TEXTURE_2D,
+                    0,
+                    $context::RGBA as i32,
+                    width as i32,
+                    height as i32,
+                    0,
+                    $context::RGBA,
+                    $context::UNSIGNED_BYTE,
+                    None,
+                )
+                .is_ok();
+            gl.bind_framebuffer($context::FRAMEBUFFER, Some(&pass.mask_framebuffer));
+            gl.framebuffer_texture_2d(
+                $context::FRAMEBUFFER,
+                $context::COLOR_ATTACHMENT0,
+                $context::TEXTURE_2D,
+                Some(&pass.mask_target),
+                0,
+            );
+            valid &= gl.check_framebuffer_status($context::FRAMEBUFFER)
+                == $context::FRAMEBUFFER_COMPLETE;
+
+            gl.bind_framebuffer($context::FRAMEBUFFER, None);
+            gl.bind_texture($context::TEXTURE_2D, None);
+
+            valid
+        }
+
+        /// Draw the full-screen quad of the capture with `program`.
+        fn draw_quad(&self, program: &WebGlProgram, a_position: u32) {
+            let gl = &self.context;
+            gl.use_program(Some(program));
+            gl.bind_buffer($context::ARRAY_BUFFER, self.coords());
+            gl.bind_buffer($context::ELEMENT_ARRAY_BUFFER, self.indices());
+            gl.vertex_attrib_pointer_with_i32(a_position, 2, $context::FLOAT, false, 0, 0);
+            gl.enable_vertex_attrib_array(a_position);
+            gl.draw_elements_with_i32($context::TRIANGLES, 6, $context::UNSIGNED_SHORT, 0);
+        }
+
+        fn temporal_pass(&self) -> Option<TemporalPass> {
+            let gl = &self.context;
+            // 8-bit history stalls short of the frame, so it falls back to the CPU
+            let extension = |name| gl.get_extension(name).ok().flatten().is_some();
+            let format = if $version == "webgl2" {
+                if extension("EXT_color_buffer_float") {
+                    Some((RGBA32F, $context::FLOAT))
+                } else if extension("EXT_color_buffer_half_float") {
+                    Some((RGBA16F, HALF_FLOAT))
+                } else {
+                    None
+                }
+            } else if extension("OES_texture_half_float") && extension("EXT_color_buffer_half_float") {
+                Some(($context::RGBA, HALF_FLOAT_OES))
+            } else {
+                None
+            };
+
+            let shaders = [
+                initialize!(shader gl, $context::VERTEX_SHADER, include_str!("glsl/quad.vert"))?,
+                initialize!(shader gl, $context::FRAGMENT_SHADER, include_str!("glsl/temporal.frag"))?,
+                initialize!(shader gl, $context::FRAGMENT_SHADER, include_str!("glsl/copy.frag"))?,
+            ];
+            let pass = TemporalPass {
+                blend: initialize!(program gl, &shaders[0], &shaders[1])?,
+                present: initialize!(program gl, &shaders[0], &shaders[2])?,
+                frame: gl.create_texture()?,
+                history: [gl.create_texture()?, gl.create_texture()?],
+                framebuffers: [gl.create_framebuffer()?, gl.create_framebuffer()?],
+                format,
+                current: Cell::new(0),
+                size: Cell::new((0, 0)),
+                frames: Cell::new(0),
+                u_frame: None,
+                u_history: None,
+                u_weight: None,
+                u_threshold: None,
+                u_texture: None,
+                a_blend: 0,
+                a_present: 0,
+                shaders,
+            };
+
+            let valid = pass.shaders.iter().all(|shader| validate!($context shader gl, shader).is_ok())
+                && validate!($context program gl, &pass.blend).is_ok()
+                && validate!($context program gl, &pass.present).is_ok();
+            let a_blend = gl.get_attrib_location(&pass.blend, "a_position");
+            let a_present = gl.get_attrib_location(&pass.present, "a_position");
+            if !valid || a_blend < 0 || a_present < 0 {
+                self.delete_temporal_pass(&pass);
+                return None;
+            }
+
+            Some(TemporalPass {
+                u_frame: gl.get_uniform_location(&pass.blend, "u_frame"),
+                u_history: gl.get_uniform_location(&pass.blend, "u_history"),
+                u_weight: gl.get_uniform_location(&pass.blend, "u_weight"),
+                u_threshold: gl.get_uniform_location(&pass.blend, "u_threshold"),
+                u_texture: gl.get_uniform_location(&pass.present, "u_texture"),
+                a_blend: a_blend as u32,
+                a_present: a_present as u32,
+                ..pass
+            })
+        }
+
+        fn delete_temporal_pass(&self, pass: &TemporalPass) {
+            let gl = &self.context;
+            pass.framebuffers.iter().for_each(|framebuffer| gl.delete_framebuffer(Some(framebuffer)));
+            pass.history.iter().for_each(|history| gl.delete_texture(Some(history)));
+            gl.delete_texture(Some(&pass.frame));
+            gl.delete_program(Some(&pass.blend));
+            gl.delete_program(Some(&pass.present));
+            pass.shaders.iter().for_each(|shader| gl.delete_shader(Some(shader)));
+        }
+
+        /// Allocate the history textures of `size`, `false` when they cannot be rendered into.
+        fn resize_temporal(&self, pass: &TemporalPass, (width, height): (u32, u32)) -> bool {
+            let gl = &self.context;
+            let Some((format, kind)) = pass.format else {
+                return false;
+            };
+            let mut valid = true;
+
+            for (history, framebuffer) in pass.history.iter().zip(&pass.framebuffers) {
+                gl.bind_texture($context::TEXTURE_2D, Some(history));
+                initialize!($context texture gl);
+                valid &= gl
+                    .tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+                        $context::TEXTURE_2D,
+                        0,
+                        format as i32,
+                        width as i32,
+                        height as i32,
+                        0,
+                        $context::RGBA,
+                        kind,
+                        None,
+                    )
+                    .is_ok();
+
+                gl.bind_framebuffer($context::FRAMEBUFFER, Some(framebuffer));
+                gl.framebuffer_texture_2d(
+                    $context::FRAMEBUFFER,
+                    $context::COLOR_ATTACHMENT0,
+                    $context::TEXTURE_2D,
+                    Some(history),
+                    0,
+                );
+                valid &= gl.check_framebuffer_status($context::FRAMEBUFFER)
+                    == $context::FRAMEBUFFER_COMPLETE;
+            }
+            gl.bind_framebuffer($context::FRAMEBUFFER, None);
+            gl.bind_texture($context::TEXTURE_2D, None);
+
+            valid
+        }
+
+        /// Blend the captured frame with the history of the temporal filter,
+        /// on the CPU when the context has no float targets.
+        fn filter_temporal(&self, (width, height): (u32, u32)) {
+            let Some(mode) = self.temporal.get() else {
+                return;
+            };
+            let Some(pass) = self.temporal_pass.get_or_init(|| self.temporal_pass()).as_ref() else {
+                return;
+            };
+            if width * height == 0 {
+                return;
+            }
+
+            if pass.size.get() != (width, height) {
+                if !self.resize_temporal(pass, (width, height)) {
+                    pass.size.set((0, 0));
+                    self.filter_temporal_cpu(pass, mode, (width, height));
+                    return;
+                }
+                pass.size.set((width, height));
+                pass.frames.set(0);
+            }
+            pass.frames.set(pass.frames.get().saturating_add(1));
+            let (weight, threshold) = mode.weights(pass.frames.get());
+
+            let gl = &self.context;
+            self.copy_frame(&pass.frame, (width, height));
+
+            let next = pass.current.get() ^ 1;
+            gl.bind_framebuffer($context::FRAMEBUFFER, Some(&pass.framebuffers[next]));
+            gl.viewport(0, 0, width as i32, height as i32);
+            gl.active_texture($context::TEXTURE1);
+            gl.bind_texture($context::TEXTURE_2D, Some(&pass.history[next ^ 1]));
+            gl.active_texture($context::TEXTURE0);
+            gl.bind_texture($context::TEXTURE_2D, Some(&pass.frame));
+            gl.use_program(Some(&pass.blend));
+            gl.uniform1i(pass.u_frame.as_ref(), 0);
+            gl.uniform1i(pass.u_history.as_ref(), 1);
+            gl.uniform1f(pass.u_weight.as_ref(), weight);
+            gl.uniform1f(pass.u_threshold.as_ref(), threshold);
+            self.draw_quad(&pass.blend, pass.a_blend);
+            pass.current.set(next);
+
+            gl.active_texture($context::TEXTURE1);
+            gl.bind_texture($context::TEXTURE_2D, None);
+            gl.active_texture($context::TEXTURE0);
+            gl.disable_vertex_attrib_array(pass.a_blend);
+            self.present_temporal(pass, &pass.history[next]);
+        }
+
+        /// Blend the captured frame with the history kept on the CPU.
+        fn filter_temporal_cpu(&self, pass: &TemporalPass, mode: crate::TemporalMode, (width, height): (u32, u32)) {
+            let mut frame = vec![0; (width * height * 4) as usize];
+            self.retrieve_rect(0, 0, width, height, &mut frame);
+            self.temporal_cpu
+                .borrow_mut()
+                .get_or_insert_with(|| crate::TemporalFilter::new(mode))
+                .apply(&mut frame, (width, height));
+
+            // the frame is packed top-down like the copies of the framebuffer
+            let gl = &self.context;
+            gl.bind_texture($context::TEXTURE_2D, Some(&pass.frame));
+            initialize!($context texture gl);
+            gl.pixel_storei($context::UNPACK_FLIP_Y_WEBGL, 0);
+            let uploaded = gl
+                .tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+                    $context::TEXTURE_2D,
+                    0,
+                    $context::RGBA as i32,
+                    width as i32,
+                    height as i32,
+                    0,
+                    $context::RGBA,
+                    $context::UNSIGNED_BYTE,
+                    Some(&frame),
+                )
+                .is_ok();
+            gl.bind_texture($context::TEXTURE_2D, None);
+
+            if uploaded {
+                self.present_temporal(pass, &pass.frame);
+            }
+        }
+
+        /// Draw the filtered frame in place of the captured frame.
+        fn present_temporal(&self, pass: &TemporalPass, texture: &WebGlTexture) {
+            let gl = &self.context;
+            let (width, height) = self.capture_size();
+            gl.bind_framebuffer($context::FRAMEBUFFER, None);
+            gl.viewport(0, 0, width as i32, height as i32);
+            gl.bind_texture($context::TEXTURE_2D, Some(texture));
+            gl.use_program(Some(&pass.present));
+            gl.uniform1i(pass.u_texture.as_ref(), 0);
+            self.draw_quad(&pass.present, pass.a_present);
+
+            gl.disable_vertex_attrib_array(pass.a_present);
+            gl.bind_texture($context::TEXTURE_2D, None);
+            gl.bind_buffer($context::ELEMENT_ARRAY_BUFFER, None);
+            gl.bind_buffer($context::ARRAY_BUFFER, None);
+            gl.use_program(None);
+        }
+
+        fn motion_pass(&self) -> Option<MotionPass> {
+            let scatter = self.scatter_pass(
+                include_str!("glsl/motion.vert"),
+                include_str!("glsl/histogram.frag"),
+            )?;
+            let gl = &self.context;
+            let frames = match gl.create_texture().zip(gl.create_texture()) {
+                Some((current, previous)) => [current, previous],
+                None => {
+                    self.delete_scatter_pass(&scatter);
+                    return None;
+                }
+            };
 
-            match mode {
+            Some(MotionPass {
+                u_current: gl.get_uniform_location(&scatter.program, "u_current"),
+                u_previous: gl.get_uniform_location(&scatter.program, "u_previous"),
+                u_size: gl.get_uniform_location(&scatter.program, "u_size"),
+                u_grid: gl.get_uniform_location(&scatter.program, "u_grid"),
+                u_threshold: gl.get_uniform_location(&scatter.program, "u_threshold"),
+                scatter,
+                frames,
+                current: Cell::new(0),
+                frame_size: Cell::new((0, 0)),
+            })
+        }
+
+        /// Draw the uploaded video frame mapped through `warp` into the viewport.
+        fn draw(
+            &self,
+            source: &web_sys::HtmlVideoElement,
+            warp: &crate::Homography,
+            filter: &crate::utils::Filter,
+            (x, y, width, height): (i32, i32, u32, u32),
+        ) {
+            self.context
+                .uniform_matrix3fv_with_f32_array(self.u_warp(), false, &warp.to_gl());
+            self.context.uniform2f(
+                self.u_size(),
+                source.video_width() as f32,
+                source.video_height() as f32,
+            );
+            self.context.uniform2f(
+                self.u_footprint.as_ref(),
+                filter.footprint.0 as f32,
+                filter.footprint.1 as f32,
+            );
+
+            self.context.viewport(x, y, width as i32, height as i32);
+            self.context.draw_elements_with_i32(
+                $context::TRIANGLES,
+                6,
+                $context::UNSIGNED_SHORT,
+                0,
+            );
+        }
+
+        fn render(
+            &self,
+            source: &web_sys::HtmlVideoElement,
+            (sw, sh): (u32, u32),
+            mode: crate::CaptureMode,
+            warp: &crate::Homography,
+        ) -> (u32, u32) {
+            let (mut cw, mut ch) = self.capture_size();
+
+            let viewport = match mode {
                 crate::CaptureMode::Put(x, y) => {
                     if x > 0 || y > 0 {
                         self.clear();
@@ -293,11 +1343,11 @@ macro_rules! impl_capture_gl {
                         }
                     }
 
-                    self.context.viewport(x, y, sw as i32, sh as i32);
+                    (x, y, sw, sh)
                 }
                 crate::CaptureMode::Fill => {
                     let (cw, ch) = self.capture_size();
-                    self.context.viewport(0, 0, cw as i32, ch as i32);
+                    (0, 0, cw, ch)
                 }
                 crate::CaptureMode::Adjust => {
                     let (dw, dh) = self.capture_size();
@@ -308,7 +1358,7 @@ macro_rules! impl_capture_gl {
                     cw = sw;
                     ch = sh;
 
-                    self.context.viewport(0, 0, sw as i32, sh as i32);
+                    (0, 0, sw, sh)
                 }
                 crate::CaptureMode::Pinhole => {
                     let (cw, ch) = self.capture_size();
@@ -321,51 +1371,407 @@ macro_rules! impl_capture_gl {
                 }
             };
 
-            let _ = self
-                .context
-                .$capture_method(
-                    $context::TEXTURE_2D,
-                    0,
-                    $context::RGBA as i32,
-                    $context::RGBA,
-                    $context::UNSIGNED_BYTE,
-                    source,
-                )
-                .map(|_| {
-                    self.context.draw_elements_with_i32(
-                        $context::TRIANGLES,
-                        6,
-                        $context::UNSIGNED_SHORT,
-                        0,
-                    );
-                    self.context.flush();
-                })
-                .unwrap();
+            let filter = self.filter(source, warp, (viewport.2, viewport.3));
+
+            self.bind();
+            self.upload(source, self.mipmap(&filter));
+            self.draw(source, warp, &filter, viewport);
+            self.context.flush();
+            self.unbind();
+            self.filter_temporal((cw, ch));
+
+            (cw, ch)
+        }
+    }
+
+    impl crate::sealed::Sealed for $name {}
+
+    impl BrowserVideoCapture for $name {
+        fn capture(
+            &self,
+            source: &web_sys::HtmlVideoElement,
+            mode: crate::CaptureMode,
+        ) -> (u32, u32) {
+            let (sw, sh) = crate::utils::video_size(source);
+
+            if sw == 0 || sh == 0 {
+                return self.capture_size();
+            }
+
+            self.render(source, (sw, sh), mode, &crate::Homography::IDENTITY)
+        }
+
+        fn capture_warp(
+            &self,
+            source: &web_sys::HtmlVideoElement,
+            warp: &crate::Warp,
+            mode: crate::CaptureMode,
+        ) -> (u32, u32) {
+            let (sw, sh) = crate::utils::video_size(source);
+
+            if sw == 0 || sh == 0 {
+                return self.capture_size();
+            }
+
+            match warp.homography(sw, sh) {
+                Some(homography) => self.render(source, warp.size(), mode, &homography),
+                None => self.capture_size(),
+            }
+        }
+
+        fn capture_pyramid(
+            &self,
+            source: &web_sys::HtmlVideoElement,
+            pyramid: &crate::Pyramid,
+        ) -> (u32, u32) {
+            let (sw, sh) = crate::utils::video_size(source);
+            let (cw, ch) = pyramid.size();
+
+            if sw == 0 || sh == 0 {
+                return self.capture_size();
+            }
+
+            if self.capture_size() != (cw, ch) {
+                self.set_capture_size(cw, ch);
+            }
+            self.clear();
+
+            let warp = crate::Homography::IDENTITY;
+            let filters: Vec<_> = pyramid
+                .levels()
+                .iter()
+                .map(|level| self.filter(source, &warp, level.size()))
+                .collect();
+
+            self.bind();
+            self.upload(source, filters.iter().any(|filter| self.mipmap(filter)));
+            for (level, filter) in pyramid.levels().iter().zip(filters.iter()) {
+                let viewport = (level.x as i32, level.y as i32, level.width, level.height);
+                self.draw(source, &warp, filter, viewport);
+            }
+            self.context.flush();
+            self.unbind();
+
+            (cw, ch)
+        }
+
+        fn capture_mosaic(
+            &self,
+            sources: &[(&web_sys::HtmlVideoElement, crate::CaptureMode)],
+            mosaic: &crate::Mosaic,
+        ) -> Vec<crate::Rect> {
+            let size = mosaic.size();
+            if self.capture_size() != size {
+                self.set_capture_size(size.0, size.1);
+            }
+            self.clear();
+
+            let warp = crate::Homography::IDENTITY;
+            let mut rects = Vec::with_capacity(sources.len().min(mosaic.len()));
+
+            self.bind();
+            self.context.enable($context::SCISSOR_TEST);
+            for (index, (source, mode)) in sources.iter().enumerate().take(mosaic.len()) {
+                let (sw, sh) = crate::utils::video_size(source);
+                let placement = mosaic.placement(index, (sw, sh), *mode);
+
+                match placement {
+                    Some((viewport, rect)) if sw > 0 && sh > 0 && rect.area() > 0 => {
+                        let filter = self.filter(source, &warp, (viewport.2, viewport.3));
+                        self.upload(source, self.mipmap(&filter));
+                        self.context.scissor(
+                            rect.x as i32,
+                            rect.y as i32,
+                            rect.width as i32,
+                            rect.height as i32,
+                        );
+                        self.draw(source, &warp, &filter, viewport);
+                        rects.push(rect);
+                    }
+                    _ => rects.push(Default::default()),
+                }
+            }
+            self.context.disable($context::SCISSOR_TEST);
+            self.context.flush();
+            self.unbind();
+
+            rects
+        }
+
+        fn resample(&self) -> crate::Resample {
+            self.resample.get()
+        }
 
+        fn set_resample(&self, resample: crate::Resample) {
+            self.resample.set(resample);
+
+            self.context.use_program(self.program());
+            self.context.uniform1i(
+                self.u_resample.as_ref(),
+                match resample {
+                    crate::Resample::Nearest => 0,
+                    crate::Resample::Bilinear => 1,
+                    crate::Resample::Area => 2,
+                    crate::Resample::Bicubic => 3,
+                    crate::Resample::Lanczos => 4,
+                },
+            );
             self.context.use_program(None);
+        }
+
+        fn set_undistort(&self, undistort: Option<crate::Undistort>) {
+            self.context.use_program(self.program());
+
             self.context
-                .bind_texture($context::TEXTURE_2D, None);
-            self.context
-                .bind_buffer($context::ELEMENT_ARRAY_BUFFER, None);
-            self.context
-                .bind_buffer($context::ARRAY_BUFFER, None);
+                .uniform1i(self.u_undistort.as_ref(), undistort.is_some() as i32);
+            if let Some(undistort) = undistort {
+                self.context
+                    .uniform4fv_with_f32_array(self.u_camera.as_ref(), &undistort.camera_gl());
+                self.context
+                    .uniform3fv_with_f32_array(self.u_radial.as_ref(), &undistort.radial_gl());
+                self.context.uniform2fv_with_f32_array(
+                    self.u_tangential.as_ref(),
+                    &undistort.tangential_gl(),
+                );
+            }
 
-            (cw, ch)
+            self.context.use_program(None);
+        }
+
+        fn temporal(&self) -> Option<crate::TemporalMode> {
+            self.temporal.get()
+        }
+
+        fn set_temporal(&self, mode: Option<crate::TemporalMode>) {
+            // the filter needs the pass to present the blended frame
+            let available = self.temporal_pass.get_or_init(|| self.temporal_pass()).is_some();
+            self.temporal.set(mode.filter(|_| available));
+            self.temporal_cpu.replace(None);
+            if let Some(Some(pass)) = self.temporal_pass.get() {
+                pass.frames.set(0);
+            }
+        }
+
+        fn layout(&self) -> crate::FrameLayout {
+            self.layout.get()
+        }
+
+        fn set_layout(&self, layout: crate::FrameLayout) {
+            self.layout.set(layout);
         }
 
         fn retrieve(&self, buffer: &mut [u8]) {
+            let layout = self.layout.get();
+            let (width, height) = self.capture_size();
+            let row = width as usize * 4;
+            let stride = layout.stride(width, 4);
+
+            // let the pixel store place the rows at the stride when it can,
+            // otherwise read the packed rows and move them afterwards
+            let pack = if stride == row {
+                Some((4, 0))
+            } else if matches!(layout.alignment, 2 | 4 | 8) {
+                Some((layout.alignment as i32, 0))
+            } else if $version == "webgl2" && stride % 4 == 0 {
+                Some((4, (stride / 4) as i32))
+            } else {
+                None
+            };
+
             self.context.finish();
+            let (alignment, row_length) = pack.unwrap_or((4, 0));
+            self.context.pixel_storei($context::PACK_ALIGNMENT, alignment);
+            if row_length > 0 {
+                self.context.pixel_storei(PACK_ROW_LENGTH, row_length);
+            }
+
+            let size = if pack.is_some() { buffer.len() } else { row * height as usize };
             self.context
                 .read_pixels_with_opt_u8_array(
                     0,
                     0,
-                    self.capture_width() as i32,
-                    self.capture_height() as i32,
+                    width as i32,
+                    height as i32,
                     $context::RGBA,
                     $context::UNSIGNED_BYTE,
-                    Some(buffer),
+                    Some(&mut buffer[..size]),
                 )
                 .unwrap();
+
+            self.context.pixel_storei($context::PACK_ALIGNMENT, 4);
+            if row_length > 0 {
+                self.context.pixel_storei(PACK_ROW_LENGTH, 0);
+            }
+
+            match pack {
+                Some(_) => layout.reorder(buffer, (width, height), 4),
+                None => layout.apply(buffer, (width, height), 4),
+            }
+        }
+
+        fn retrieve_rect(&self, x: i32, y: i32, width: u32, height: u32, buffer: &mut [u8]) {
+            // rows are stored upside down, so the framebuffer origin
+            // is the top-left corner of the captured frame
+            let (cw, ch) = self.capture_size();
+            if crate::Rect::new(0, 0, cw, ch).clip(x, y, width, height).size() != (width, height) {
+                // pixels out of the framebuffer are left untouched
+                buffer.fill(0);
+            }
+
+            self.context.finish();
+            let read = self.context.read_pixels_with_opt_u8_array(
+                x,
+                y,
+                width as i32,
+                height as i32,
+                $context::RGBA,
+                $context::UNSIGNED_BYTE,
+                Some(buffer),
+            );
+            if read.is_err() {
+                // the buffer is too small for the rectangle
+                buffer.fill(0);
+            }
+        }
+
+        fn stats(&self) -> crate::FrameStats {
+            self.gpu_stats().unwrap_or_else(|| {
+                crate::FrameStats::from_frame(&self.packed_data(), self.channels_count())
+            })
+        }
+
+        fn sharpness(&self, method: crate::SharpnessMethod, rect: Option<crate::Rect>) -> f64 {
+            self.gpu_sharpness(method, rect).unwrap_or_else(|| {
+                method.score(&self.packed_data(), self.capture_size(), self.channels_count(), rect)
+            })
+        }
+
+        fn changed_cells(&self, threshold: u8, (cols, rows): (u32, u32)) -> Option<Vec<u32>> {
+            let pass = self.motion.get_or_init(|| self.motion_pass()).as_ref()?;
+            let (width, height) = self.capture_size();
+            if width * height == 0 || cols * rows == 0 {
+                return None;
+            }
+
+            let gl = &self.context;
+            while gl.get_error() != $context::NO_ERROR {}
+
+            // the current frame replaces the oldest one
+            let current = pass.current.get() ^ 1;
+            self.copy_frame(&pass.frames[current], (width, height));
+            pass.current.set(current);
+            if pass.frame_size.replace((width, height)) != (width, height) {
+                return Some(vec![0; (cols * rows) as usize]);
+            }
+            if !self.resize_scatter_target(&pass.scatter, (cols, rows)) {
+                return None;
+            }
+
+            self.begin_scatter(&pass.scatter, (width, height));
+            gl.active_texture($context::TEXTURE1);
+            gl.bind_texture($context::TEXTURE_2D, Some(&pass.frames[current ^ 1]));
+            gl.active_texture($context::TEXTURE0);
+            gl.bind_texture($context::TEXTURE_2D, Some(&pass.frames[current]));
+            gl.uniform1i(pass.u_current.as_ref(), 0);
+            gl.uniform1i(pass.u_previous.as_ref(), 1);
+            gl.uniform2f(pass.u_size.as_ref(), width as f32, height as f32);
+            gl.uniform2f(pass.u_grid.as_ref(), cols as f32, rows as f32);
+            gl.uniform1f(pass.u_threshold.as_ref(), threshold as f32);
+            gl.viewport(0, 0, cols as i32, rows as i32);
+            gl.draw_arrays($context::POINTS, 0, (width * height) as i32);
+
+            gl.active_texture($context::TEXTURE1);
+            gl.bind_texture($context::TEXTURE_2D, None);
+            gl.active_texture($context::TEXTURE0);
+            let cells = self.end_scatter(&pass.scatter)?;
+            Some(cells.into_iter().map(|count| count as u32).collect())
+        }
+
+        fn reset_motion(&self) {
+            if let Some(Some(pass)) = self.motion.get() {
+                pass.frame_size.set((0, 0));
+            }
+        }
+
+        fn update_background(&self, options: crate::BackgroundOptions) -> Option<Vec<u8>> {
+            let pass = self.background.get_or_init(|| self.background_pass()).as_ref()?;
+            let (width, height) = self.capture_size();
+            if width * height == 0 {
+                return None;
+            }
+
+            let initialize = pass.size.get() != (width, height);
+            if initialize {
+                if !self.resize_background(pass, (width, height)) {
+                    pass.size.set((0, 0));
+                    return None;
+                }
+                pass.size.set((width, height));
+            }
+
+            let gl = &self.context;
+            self.copy_frame(&pass.frame, (width, height));
+
+            // render the next model from the previous one
+            let next = pass.current.get() ^ 1;
+            gl.bind_framebuffer($context::FRAMEBUFFER, Some(&pass.framebuffers[next]));
+            gl.viewport(0, 0, width as i32, height as i32);
+            gl.active_texture($context::TEXTURE1);
+            gl.bind_texture($context::TEXTURE_2D, Some(&pass.models[next ^ 1]));
+            gl.active_texture($context::TEXTURE0);
+            gl.bind_texture($context::TEXTURE_2D, Some(&pass.frame));
+            gl.use_program(Some(&pass.update));
+            gl.uniform1i(pass.u_frame.as_ref(), 0);
+            gl.uniform1i(pass.u_model.as_ref(), 1);
+            gl.uniform1i(pass.u_initialize.as_ref(), initialize as i32);
+            gl.uniform1f(pass.u_rate.as_ref(), options.learning_rate);
+            gl.uniform1f(pass.u_threshold.as_ref(), options.threshold);
+            gl.uniform2f(
+                pass.u_variance.as_ref(),
+                crate::background::INITIAL_VARIANCE,
+                crate::background::MIN_VARIANCE,
+            );
+            self.draw_quad(&pass.update, pass.a_update);
+            pass.current.set(next);
+
+            // render the foreground flags into the mask target, the captured frame
+            // keeps its alpha channel
+            gl.bind_framebuffer($context::FRAMEBUFFER, Some(&pass.mask_framebuffer));
+            gl.active_texture($context::TEXTURE1);
+            gl.bind_texture($context::TEXTURE_2D, None);
+            gl.active_texture($context::TEXTURE0);
+            gl.bind_texture($context::TEXTURE_2D, Some(&pass.models[next]));
+            gl.use_program(Some(&pass.mask));
+            gl.uniform1i(pass.u_mask_model.as_ref(), 0);
+            self.draw_quad(&pass.mask, pass.a_mask);
+
+            let mut pixels = vec![0; (width * height * 4) as usize];
+            let read = gl.read_pixels_with_opt_u8_array(
+                0,
+                0,
+                width as i32,
+                height as i32,
+                $context::RGBA,
+                $context::UNSIGNED_BYTE,
+                Some(&mut pixels),
+            );
+            gl.bind_framebuffer($context::FRAMEBUFFER, None);
+
+            gl.disable_vertex_attrib_array(pass.a_mask);
+            gl.disable_vertex_attrib_array(pass.a_update);
+            gl.bind_texture($context::TEXTURE_2D, None);
+            gl.bind_buffer($context::ELEMENT_ARRAY_BUFFER, None);
+            gl.bind_buffer($context::ARRAY_BUFFER, None);
+            gl.use_program(None);
+
+            read.ok()?;
+            Some(pixels.chunks_exact(4).map(|pixel| if pixel[0] >= 128 { 255 } else { 0 }).collect())
+        }
+
+        fn reset_background(&self) {
+            if let Some(Some(pass)) = self.background.get() {
+                pass.size.set((0, 0));
+            }
         }
 
         fn clear(&self) {
@@ -393,6 +1799,25 @@ macro_rules! impl_capture_gl {
 
             gl.delete_shader(self.vertex.as_ref());
             gl.delete_shader(self.fragment.as_ref());
+
+            if let Some(Some(pass)) = self.stats.get() {
+                self.delete_scatter_pass(&pass.scatter);
+                gl.delete_texture(Some(&pass.source));
+            }
+            if let Some(Some(pass)) = self.sharpness.get() {
+                self.delete_scatter_pass(&pass.scatter);
+                gl.delete_texture(Some(&pass.source));
+            }
+            if let Some(Some(pass)) = self.background.get() {
+                self.delete_background_pass(pass);
+            }
+            if let Some(Some(pass)) = self.temporal_pass.get() {
+                self.delete_temporal_pass(pass);
+            }
+            if let Some(Some(pass)) = self.motion.get() {
+                self.delete_scatter_pass(&pass.scatter);
+                pass.frames.iter().for_each(|frame| gl.delete_texture(Some(frame)));
+            }
         }
     }
     };
@@ -494,6 +1919,12 @@ pub mod html {
         tex_image_2d_with_u32_and_u32_and_html_video_element,
         "webgl2"
     );
+
+    #[cfg(all(feature = "stream", feature = "webgl"))]
+    impl_canvas_stream!(HtmlCaptureGL);
+
+    #[cfg(all(feature = "stream", feature = "webgl2"))]
+    impl_canvas_stream!(HtmlCaptureGL2);
 }
 
 #[cfg(feature = "offscreen")]
@@ -0,0 +1,30 @@
+precision highp float;
+uniform sampler2D u_frame;
+uniform sampler2D u_model;
+uniform bool u_initialize;
+uniform float u_rate;
+uniform float u_threshold;
+uniform vec2 u_variance;
+varying vec2 v_uv;
+
+const vec3 LUMA = vec3(.299, .587, .114);
+
+void main() {
+  // the model keeps the mean, the variance and the foreground flag
+  float value = floor(dot(texture2D(u_frame, v_uv).rgb, LUMA) * 255. + .5);
+  if (u_initialize) {
+    gl_FragColor = vec4(value, u_variance.x, 0., 1.);
+    return;
+  }
+
+  vec4 model = texture2D(u_model, v_uv);
+  float diff = value - model.r;
+  float variance = max(model.g, u_variance.y);
+  float foreground = diff * diff > u_threshold * u_threshold * variance ? 1. : 0.;
+  gl_FragColor = vec4(
+    model.r + u_rate * diff,
+    max(variance + u_rate * (diff * diff - variance), u_variance.y),
+    foreground,
+    1.
+  );
+}
@@ -0,0 +1,5 @@
+precision mediump float;
+
+void main() {
+  gl_FragColor = vec4(1.);
+}
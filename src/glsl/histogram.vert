@@ -0,0 +1,15 @@
+precision highp float;
+attribute float a_index;
+uniform sampler2D u_texture;
+uniform vec2 u_size;
+uniform vec4 u_weights;
+
+void main() {
+  // every point is one pixel moved into the bin of its weighted value
+  float y = floor((a_index + .5) / u_size.x);
+  float x = a_index - y * u_size.x;
+  vec4 texel = texture2D(u_texture, (vec2(x, y) + .5) / u_size);
+  float bin = floor(dot(texel, u_weights) * 255. + .5);
+  gl_Position = vec4((bin + .5) / 128. - 1., 0., 0., 1.);
+  gl_PointSize = 1.;
+}
@@ -0,0 +1,8 @@
+precision highp float;
+uniform sampler2D u_model;
+varying vec2 v_uv;
+
+void main() {
+  float foreground = texture2D(u_model, v_uv).b;
+  gl_FragColor = vec4(foreground, foreground, foreground, 1.);
+}
@@ -0,0 +1,22 @@
+precision highp float;
+attribute float a_index;
+uniform sampler2D u_current;
+uniform sampler2D u_previous;
+uniform vec2 u_size;
+uniform vec2 u_grid;
+uniform float u_threshold;
+
+const vec3 LUMA = vec3(.299, .587, .114);
+
+void main() {
+  float y = floor((a_index + .5) / u_size.x);
+  float x = a_index - y * u_size.x;
+  vec2 uv = (vec2(x, y) + .5) / u_size;
+  float diff = abs(dot(texture2D(u_current, uv).rgb - texture2D(u_previous, uv).rgb, LUMA)) * 255.;
+  vec2 cell = floor(vec2(x, y) * u_grid / u_size);
+  // unchanged pixels are moved out of the target
+  gl_Position = diff > u_threshold
+    ? vec4((cell + .5) / u_grid * 2. - 1., 0., 1.)
+    : vec4(2., 2., 0., 1.);
+  gl_PointSize = 1.;
+}
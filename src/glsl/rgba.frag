@@ -1,8 +1,5 @@
-precision highp float;
-uniform sampler2D u_texture;
-varying vec2 v_texCoord;
 
 void main() {
-   vec4 pixel = texture2D(u_texture, v_texCoord);
+   vec4 pixel = source(v_texCoord);
    gl_FragColor = pixel;
 }
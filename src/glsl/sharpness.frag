@@ -0,0 +1,6 @@
+precision highp float;
+varying float v_value;
+
+void main() {
+  gl_FragColor = vec4(v_value);
+}
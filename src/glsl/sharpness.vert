@@ -0,0 +1,40 @@
+precision highp float;
+attribute float a_index;
+uniform sampler2D u_texture;
+uniform vec2 u_size;
+uniform vec4 u_rect;
+uniform vec2 u_grid;
+uniform float u_tenengrad;
+uniform float u_square;
+varying float v_value;
+
+const vec3 LUMA = vec3(.299, .587, .114);
+
+float luma(vec2 pixel) {
+  // neighbors out of the frame repeat the edge pixels
+  vec2 uv = (clamp(pixel, vec2(0.), u_size - 1.) + .5) / u_size;
+  return floor(dot(texture2D(u_texture, uv).rgb, LUMA) * 255. + .5);
+}
+
+void main() {
+  float y = floor((a_index + .5) / u_rect.z);
+  float x = a_index - y * u_rect.z;
+  vec2 pixel = u_rect.xy + vec2(x, y);
+
+  if (u_tenengrad > 0.) {
+    float gx = luma(pixel + vec2(1., -1.)) + 2. * luma(pixel + vec2(1., 0.)) + luma(pixel + vec2(1., 1.))
+      - luma(pixel + vec2(-1., -1.)) - 2. * luma(pixel + vec2(-1., 0.)) - luma(pixel + vec2(-1., 1.));
+    float gy = luma(pixel + vec2(-1., 1.)) + 2. * luma(pixel + vec2(0., 1.)) + luma(pixel + vec2(1., 1.))
+      - luma(pixel + vec2(-1., -1.)) - 2. * luma(pixel + vec2(0., -1.)) - luma(pixel + vec2(1., -1.));
+    v_value = gx * gx + gy * gy;
+  } else {
+    float laplacian = luma(pixel + vec2(-1., 0.)) + luma(pixel + vec2(1., 0.))
+      + luma(pixel + vec2(0., -1.)) + luma(pixel + vec2(0., 1.)) - 4. * luma(pixel);
+    v_value = u_square > 0. ? laplacian * laplacian : laplacian;
+  }
+
+  // spread the sums over the grid cells to keep the float precision
+  vec2 cell = floor(vec2(x, y) * u_grid / u_rect.zw);
+  gl_Position = vec4((cell + .5) / u_grid * 2. - 1., 0., 1.);
+  gl_PointSize = 1.;
+}
@@ -0,0 +1,89 @@
+precision highp float;
+uniform sampler2D u_texture;
+uniform vec2 u_size;
+uniform mat3 u_warp;
+uniform bool u_undistort;
+uniform vec4 u_camera;
+uniform vec3 u_radial;
+uniform vec2 u_tangential;
+uniform int u_resample;
+uniform vec2 u_footprint;
+varying vec2 v_texCoord;
+
+const float PI = 3.14159265;
+
+vec2 distort(vec2 uv) {
+   vec2 p = (uv * u_size - u_camera.zw) / u_camera.xy;
+   float r2 = dot(p, p);
+   float radial = 1. + r2 * (u_radial.x + r2 * (u_radial.y + r2 * u_radial.z));
+   vec2 tangential = vec2(
+      2. * u_tangential.x * p.x * p.y + u_tangential.y * (r2 + 2. * p.x * p.x),
+      u_tangential.x * (r2 + 2. * p.y * p.y) + 2. * u_tangential.y * p.x * p.y
+   );
+   return ((p * radial + tangential) * u_camera.xy + u_camera.zw) / u_size;
+}
+
+vec4 texel(vec2 uv) {
+   return texture2D(u_texture, vec2(uv.x, 1. - uv.y));
+}
+
+float kernel(float x) {
+   x = abs(x);
+   if (u_resample == 3) {
+      if (x < 1.) return (1.5 * x - 2.5) * x * x + 1.;
+      if (x < 2.) return ((-0.5 * x + 2.5) * x - 4.) * x + 2.;
+      return 0.;
+   }
+   if (x < 1e-5) return 1.;
+   if (x >= 3.) return 0.;
+   float px = PI * x;
+   return 3. * sin(px) * sin(px / 3.) / (px * px);
+}
+
+vec4 resample(vec2 uv) {
+   // nearest and bilinear are done by the texture filter
+   if (u_resample < 2) {
+      return texel(uv);
+   }
+
+   vec2 scale = max(u_footprint, vec2(1.));
+
+   if (u_resample == 2) {
+      vec4 sum = vec4(0.);
+      for (int j = 0; j < 4; j++) {
+         for (int i = 0; i < 4; i++) {
+            vec2 offset = (vec2(float(i), float(j)) + .5) / 4. - .5;
+            sum += texel(uv + offset * scale / u_size);
+         }
+      }
+      return sum / 16.;
+   }
+
+   vec2 center = uv * u_size - .5;
+   vec2 f = fract(center);
+   vec4 sum = vec4(0.);
+   float total = 0.;
+   for (int j = -2; j <= 3; j++) {
+      for (int i = -2; i <= 3; i++) {
+         vec2 k = vec2(float(i), float(j)) - f;
+         float weight = kernel(k.x) * kernel(k.y);
+         if (weight != 0.) {
+            sum += weight * texel((center + k * scale + .5) / u_size);
+            total += weight;
+         }
+      }
+   }
+   return sum / total;
+}
+
+vec4 source(vec2 coord) {
+   vec3 point = u_warp * vec3(coord.x, 1. - coord.y, 1.);
+   vec2 uv = point.xy / point.z;
+   if (u_undistort) {
+      uv = distort(uv);
+   }
+   if (any(lessThan(uv, vec2(0.))) || any(greaterThan(uv, vec2(1.)))) {
+      return vec4(0.);
+   }
+   return resample(uv);
+}
@@ -0,0 +1,21 @@
+precision highp float;
+uniform sampler2D u_frame;
+uniform sampler2D u_history;
+uniform float u_weight;
+uniform float u_threshold;
+varying vec2 v_uv;
+
+const vec3 LUMA = vec3(.299, .587, .114);
+
+void main() {
+  vec4 frame = texture2D(u_frame, v_uv);
+  vec4 history = texture2D(u_history, v_uv);
+
+  // moving pixels take the new frame to avoid ghosting
+  float weight = u_weight;
+  if (u_threshold > 0.) {
+    float diff = abs(dot(frame.rgb - history.rgb, LUMA));
+    weight = mix(weight, 1., smoothstep(.5 * u_threshold, u_threshold, diff));
+  }
+  gl_FragColor = mix(history, frame, weight);
+}
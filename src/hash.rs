@@ -0,0 +1,181 @@
+use std::fmt;
+
+use crate::{stats::luma, BrowserVideoCapture};
+
+/// Perceptual hash algorithm.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HashMethod {
+    /// Pixels of the 8x8 luminance brighter than its mean.
+    Average,
+    /// Pixels of the 9x8 luminance brighter than their left neighbors.
+    Difference,
+    /// Low frequencies of the DCT of the 32x32 luminance above their median.
+    #[default]
+    Perceptual,
+}
+
+impl HashMethod {
+    /// Get the size of the downscaled frame the hash is computed from,
+    /// the capture of this size with [`CaptureMode::Fill`](crate::CaptureMode::Fill)
+    /// skips the downscaling on the CPU.
+    pub const fn input_size(&self) -> (u32, u32) {
+        match self {
+            HashMethod::Average => (8, 8),
+            HashMethod::Difference => (9, 8),
+            HashMethod::Perceptual => (32, 32),
+        }
+    }
+
+    /// Hash the raw frame with `channels` bytes per pixel.
+    pub fn hash(&self, buffer: &[u8], size: (u32, u32), channels: u32) -> ImageHash {
+        let input = downscale(buffer, size, channels, self.input_size());
+        let bits = match self {
+            HashMethod::Average => {
+                let mean = input.iter().sum::<f64>() / input.len() as f64;
+                input.iter().map(|&value| value > mean).collect::<Vec<_>>()
+            }
+            HashMethod::Difference => input
+                .chunks_exact(9)
+                .flat_map(|row| row.windows(2).map(|pair| pair[1] > pair[0]))
+                .collect(),
+            HashMethod::Perceptual => {
+                let coefficients = dct_low(&input, 32, 8);
+                let mut sorted = coefficients.clone();
+                sorted.sort_by(f64::total_cmp);
+                let median = (sorted[31] + sorted[32]) / 2.0;
+                coefficients.iter().map(|&value| value > median).collect()
+            }
+        };
+
+        ImageHash(bits.iter().fold(0, |hash, &bit| hash << 1 | bit as u64))
+    }
+
+    /// Hash the captured frame, such as the frame captured into the small canvas
+    /// of [`HashMethod::input_size`].
+    pub fn hash_capture(&self, capture: &(impl BrowserVideoCapture + ?Sized)) -> ImageHash {
+        self.hash(
+            &capture.packed_data(),
+            capture.capture_size(),
+            capture.channels_count(),
+        )
+    }
+}
+
+/// 64-bit perceptual hash, the bits go row by row from the most significant one.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ImageHash(pub u64);
+
+impl ImageHash {
+    /// Get the Hamming distance, the number of different bits.
+    pub fn distance(&self, other: &ImageHash) -> u32 {
+        (self.0 ^ other.0).count_ones()
+    }
+
+    /// Check if the hashes are at most `threshold` bits apart, such as `10`
+    /// for the frames of the same scene.
+    pub fn is_similar(&self, other: &ImageHash, threshold: u32) -> bool {
+        self.distance(other) <= threshold
+    }
+
+    /// Find the closest reference as its index and the distance,
+    /// the first one of equal distances.
+    pub fn nearest(&self, references: &[ImageHash]) -> Option<(usize, u32)> {
+        references
+            .iter()
+            .enumerate()
+            .map(|(index, reference)| (index, self.distance(reference)))
+            .min_by_key(|&(_, distance)| distance)
+    }
+
+    /// Get the indices of the hashes which are not similar to any kept one before them,
+    /// to remove the duplicate frames.
+    pub fn unique(hashes: &[ImageHash], threshold: u32) -> Vec<usize> {
+        let mut kept: Vec<usize> = Vec::new();
+        for (index, hash) in hashes.iter().enumerate() {
+            if !kept.iter().any(|&k| hashes[k].is_similar(hash, threshold)) {
+                kept.push(index);
+            }
+        }
+        kept
+    }
+}
+
+impl fmt::Display for ImageHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// Downscale the luminance of the frame with the box filter,
+/// every target pixel averages at least one source pixel.
+fn downscale(
+    buffer: &[u8],
+    (width, height): (u32, u32),
+    channels: u32,
+    (target_width, target_height): (u32, u32),
+) -> Vec<f64> {
+    let mut target = vec![0.0; (target_width * target_height) as usize];
+    if width * height == 0 || buffer.len() < (width * height * channels) as usize {
+        return target;
+    }
+
+    let range = |index: u32, size: u32, target: u32| {
+        let start = (index * size / target).min(size - 1);
+        let end = ((index + 1) * size / target).max(start + 1);
+        start as usize..end as usize
+    };
+    let value = |x: usize, y: usize| {
+        let index = (y * width as usize + x) * channels as usize;
+        let value = match buffer[index..index + channels as usize] {
+            [l] | [l, _] => l,
+            [r, g, b, ..] => luma(r, g, b),
+            [] => unreachable!(),
+        };
+        value as f64
+    };
+
+    for ty in 0..target_height {
+        let rows = range(ty, height, target_height);
+        for tx in 0..target_width {
+            let columns = range(tx, width, target_width);
+            let count = (rows.len() * columns.len()) as f64;
+            let sum: f64 = rows
+                .clone()
+                .flat_map(|y| columns.clone().map(move |x| (x, y)))
+                .map(|(x, y)| value(x, y))
+                .sum();
+            target[(ty * target_width + tx) as usize] = sum / count;
+        }
+    }
+
+    target
+}
+
+/// Get the `count` x `count` lowest frequencies of the 2D DCT-II
+/// of the `size` x `size` block row by row.
+fn dct_low(block: &[f64], size: usize, count: usize) -> Vec<f64> {
+    let basis: Vec<f64> = (0..count * size)
+        .map(|index| {
+            let (k, n) = (index / size, index % size);
+            (std::f64::consts::PI / size as f64 * (n as f64 + 0.5) * k as f64).cos()
+        })
+        .collect();
+
+    // transform the rows, then the columns of the low frequencies
+    let rows: Vec<f64> = block
+        .chunks_exact(size)
+        .flat_map(|row| {
+            basis
+                .chunks_exact(size)
+                .map(move |cosines| row.iter().zip(cosines).map(|(a, b)| a * b).sum::<f64>())
+        })
+        .collect();
+
+    let mut coefficients = vec![0.0; count * count];
+    for (v, cosines) in basis.chunks_exact(size).enumerate() {
+        for u in 0..count {
+            coefficients[v * count + u] = (0..size).map(|y| rows[y * count + u] * cosines[y]).sum();
+        }
+    }
+    coefficients
+}
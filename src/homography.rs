@@ -0,0 +1,199 @@
+use crate::Resample;
+
+/// Tolerance below which a pivot or a determinant is treated as zero.
+const SINGULAR: f64 = 1e-10;
+
+/// Projective transform of the plane stored as a row-major 3x3 matrix.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Homography(pub [f64; 9]);
+
+impl Default for Homography {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Homography {
+    pub const IDENTITY: Self = Self([1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0]);
+
+    /// Solve the homography mapping each `from` point onto the matching `to` point.
+    ///
+    /// Returns `None` when the points are degenerate (three of them collinear).
+    pub fn from_points(from: [(f64, f64); 4], to: [(f64, f64); 4]) -> Option<Self> {
+        let mut system = [[0.0; 9]; 8];
+
+        for (i, (&(x, y), &(u, v))) in from.iter().zip(to.iter()).enumerate() {
+            system[2 * i] = [x, y, 1.0, 0.0, 0.0, 0.0, -x * u, -y * u, u];
+            system[2 * i + 1] = [0.0, 0.0, 0.0, x, y, 1.0, -x * v, -y * v, v];
+        }
+
+        for col in 0..8 {
+            let pivot = (col..8)
+                .max_by(|&a, &b| system[a][col].abs().total_cmp(&system[b][col].abs()))
+                .unwrap();
+
+            if system[pivot][col].abs() < SINGULAR {
+                return None;
+            }
+            system.swap(col, pivot);
+
+            let pivot = system[col];
+            for (_, row) in system.iter_mut().enumerate().filter(|(i, _)| *i != col) {
+                let factor = row[col] / pivot[col];
+                for (value, pivot) in row.iter_mut().zip(pivot.iter()).skip(col) {
+                    *value -= factor * pivot;
+                }
+            }
+        }
+
+        let mut matrix = [1.0; 9];
+        for (i, row) in system.iter().enumerate() {
+            matrix[i] = row[8] / row[i];
+        }
+
+        Some(Self(matrix)).filter(|h| h.determinant().abs() >= SINGULAR)
+    }
+
+    /// Solve the homography mapping the unit square corners
+    /// `(0, 0)`, `(1, 0)`, `(1, 1)`, `(0, 1)` onto `quad`.
+    pub fn from_unit_square(quad: [(f64, f64); 4]) -> Option<Self> {
+        Self::from_points([(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)], quad)
+    }
+
+    pub fn determinant(&self) -> f64 {
+        let [a, b, c, d, e, f, g, h, i] = self.0;
+        a * (e * i - f * h) - b * (d * i - f * g) + c * (d * h - e * g)
+    }
+
+    /// Get the inverse transform if the matrix is not singular.
+    pub fn inverse(&self) -> Option<Self> {
+        let [a, b, c, d, e, f, g, h, i] = self.0;
+
+        let det = self.determinant();
+        if det.abs() < SINGULAR {
+            return None;
+        }
+
+        Some(Self(
+            [
+                e * i - f * h,
+                c * h - b * i,
+                b * f - c * e,
+                f * g - d * i,
+                a * i - c * g,
+                c * d - a * f,
+                d * h - e * g,
+                b * g - a * h,
+                a * e - b * d,
+            ]
+            .map(|value| value / det),
+        ))
+    }
+
+    /// Get the transform applying `self` first and `other` after.
+    pub fn then(&self, other: &Self) -> Self {
+        let (a, b) = (&other.0, &self.0);
+        let mut matrix = [0.0; 9];
+        for row in 0..3 {
+            for col in 0..3 {
+                matrix[row * 3 + col] = (0..3).map(|k| a[row * 3 + k] * b[k * 3 + col]).sum();
+            }
+        }
+        Self(matrix)
+    }
+
+    /// Map the point, returns `None` for points sent to infinity.
+    pub fn map(&self, x: f64, y: f64) -> Option<(f64, f64)> {
+        let m = &self.0;
+        let w = m[6] * x + m[7] * y + m[8];
+
+        (w.abs() > f64::EPSILON).then(|| {
+            (
+                (m[0] * x + m[1] * y + m[2]) / w,
+                (m[3] * x + m[4] * y + m[5]) / w,
+            )
+        })
+    }
+
+    /// Get the matrix in column-major order as expected by `uniformMatrix3fv`.
+    pub fn to_gl(&self) -> [f32; 9] {
+        let m = &self.0;
+        [m[0], m[3], m[6], m[1], m[4], m[7], m[2], m[5], m[8]].map(|value| value as f32)
+    }
+}
+
+/// Quadrilateral region of the video frame rectified into a rectangle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Warp {
+    /// Source corners in video pixels:
+    /// top-left, top-right, bottom-right and bottom-left.
+    pub corners: [(f64, f64); 4],
+    /// Width of the rectified output in pixels.
+    pub width: u32,
+    /// Height of the rectified output in pixels.
+    pub height: u32,
+}
+
+impl Warp {
+    pub fn new(corners: [(f64, f64); 4], width: u32, height: u32) -> Self {
+        Self {
+            corners,
+            width,
+            height,
+        }
+    }
+
+    /// Get the size of the rectified output in pixels.
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Get the homography from normalized output coordinates
+    /// to normalized source coordinates for the source of given size.
+    pub fn homography(&self, source_width: u32, source_height: u32) -> Option<Homography> {
+        let (sw, sh) = (source_width as f64, source_height as f64);
+        Homography::from_unit_square(self.corners.map(|(x, y)| (x / sw, y / sh)))
+    }
+}
+
+/// Render the RGBA `source` into the RGBA `target` by inverse mapping
+/// each target pixel through `homography` with bilinear sampling.
+///
+/// The homography maps normalized target coordinates to normalized source
+/// coordinates, pixels mapped outside of the source are transparent.
+pub fn warp_rgba(
+    source: &[u8],
+    source_size: (u32, u32),
+    homography: &Homography,
+    target: &mut [u8],
+    target_size: (u32, u32),
+) {
+    warp_rgba_with(
+        source,
+        source_size,
+        homography,
+        target,
+        target_size,
+        Resample::Bilinear,
+    );
+}
+
+/// Render the RGBA `source` into the RGBA `target` as [`warp_rgba`]
+/// sampled with the `resample` filter.
+pub fn warp_rgba_with(
+    source: &[u8],
+    source_size: (u32, u32),
+    homography: &Homography,
+    target: &mut [u8],
+    target_size: (u32, u32),
+    resample: Resample,
+) {
+    crate::utils::remap_rgba(
+        source,
+        source_size,
+        target,
+        target_size,
+        resample,
+        |x, y| homography.map(x, y),
+    );
+}
@@ -0,0 +1,163 @@
+/// Order of the rows in the frame buffer.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RowOrder {
+    /// The first row is the top of the frame.
+    #[default]
+    TopDown,
+    /// The first row is the bottom of the frame.
+    BottomUp,
+}
+
+/// Order of the channels in the four-channel pixel.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChannelOrder {
+    #[default]
+    Rgba,
+    Bgra,
+    Argb,
+}
+
+impl ChannelOrder {
+    /// Reorder the RGBA pixel.
+    fn arrange(&self, pixel: &mut [u8]) {
+        match self {
+            ChannelOrder::Rgba => {}
+            ChannelOrder::Bgra => pixel.swap(0, 2),
+            ChannelOrder::Argb => pixel.rotate_right(1),
+        }
+    }
+
+    /// Reorder the pixel back into RGBA.
+    fn restore(&self, pixel: &mut [u8]) {
+        match self {
+            ChannelOrder::Rgba => {}
+            ChannelOrder::Bgra => pixel.swap(0, 2),
+            ChannelOrder::Argb => pixel.rotate_left(1),
+        }
+    }
+}
+
+/// Memory layout of the retrieved frame.
+///
+/// The default layout is tightly packed top-down RGBA rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FrameLayout {
+    /// Rows start at multiples of `alignment` bytes, such as `4`, `16` or `64`.
+    pub alignment: usize,
+    pub row_order: RowOrder,
+    /// Order of the channels, applies only to four-channel frames.
+    pub channel_order: ChannelOrder,
+}
+
+impl Default for FrameLayout {
+    fn default() -> Self {
+        Self {
+            alignment: 1,
+            row_order: Default::default(),
+            channel_order: Default::default(),
+        }
+    }
+}
+
+impl FrameLayout {
+    pub fn alignment(mut self, value: usize) -> Self {
+        self.alignment = value.max(1);
+        self
+    }
+
+    pub fn row_order(mut self, value: RowOrder) -> Self {
+        self.row_order = value;
+        self
+    }
+
+    pub fn channel_order(mut self, value: ChannelOrder) -> Self {
+        self.channel_order = value;
+        self
+    }
+
+    /// Check if the layout is tightly packed top-down RGBA for any width.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Get the number of bytes from the start of one row to the next one.
+    pub fn stride(&self, width: u32, channels: u32) -> usize {
+        (width as usize * channels as usize).next_multiple_of(self.alignment.max(1))
+    }
+
+    /// Get the size of the frame buffer in bytes.
+    pub fn buffer_size(&self, (width, height): (u32, u32), channels: u32) -> usize {
+        self.stride(width, channels) * height as usize
+    }
+
+    /// Rearrange the tightly packed top-down RGBA frame at the start of the buffer
+    /// into the layout in place, the buffer must have [`FrameLayout::buffer_size`] bytes.
+    pub fn apply(&self, buffer: &mut [u8], (width, height): (u32, u32), channels: u32) {
+        let row = width as usize * channels as usize;
+        let stride = self.stride(width, channels);
+
+        // move the rows from the last one, so none is overwritten before it moves
+        if stride != row {
+            for y in (1..height as usize).rev() {
+                buffer.copy_within(y * row..(y + 1) * row, y * stride);
+            }
+        }
+
+        self.reorder(buffer, (width, height), channels);
+    }
+
+    /// Reorder the rows and the channels of the top-down RGBA frame
+    /// which rows are already placed at the stride.
+    pub(crate) fn reorder(&self, buffer: &mut [u8], (width, height): (u32, u32), channels: u32) {
+        let row = width as usize * channels as usize;
+        let stride = self.stride(width, channels);
+        let height = height as usize;
+        if row == 0 {
+            return;
+        }
+
+        if channels == 4 && self.channel_order != ChannelOrder::Rgba {
+            for line in buffer.chunks_mut(stride).take(height) {
+                line[..row]
+                    .chunks_exact_mut(4)
+                    .for_each(|pixel| self.channel_order.arrange(pixel));
+            }
+        }
+
+        if self.row_order == RowOrder::BottomUp {
+            for y in 0..height / 2 {
+                let (top, bottom) = buffer.split_at_mut((height - 1 - y) * stride);
+                top[y * stride..y * stride + row].swap_with_slice(&mut bottom[..row]);
+            }
+        }
+    }
+
+    /// Copy the frame in the layout into the tightly packed top-down RGBA `target`.
+    pub fn pack(
+        &self,
+        buffer: &[u8],
+        (width, height): (u32, u32),
+        channels: u32,
+        target: &mut [u8],
+    ) {
+        let row = width as usize * channels as usize;
+        let stride = self.stride(width, channels);
+        let height = height as usize;
+        if row == 0 {
+            return;
+        }
+
+        for (y, line) in target.chunks_exact_mut(row).take(height).enumerate() {
+            let source = match self.row_order {
+                RowOrder::TopDown => y,
+                RowOrder::BottomUp => height - 1 - y,
+            } * stride;
+            line.copy_from_slice(&buffer[source..source + row]);
+
+            if channels == 4 {
+                line.chunks_exact_mut(4)
+                    .for_each(|pixel| self.channel_order.restore(pixel));
+            }
+        }
+    }
+}
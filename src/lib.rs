@@ -1,6 +1,35 @@
 #[macro_use]
 mod macros;
+#[cfg(any(feature = "gif", feature = "apng"))]
+mod animation;
+mod atlas;
+mod avi;
+mod background;
+#[cfg(feature = "blob")]
+mod blob;
+#[cfg(any(feature = "png", feature = "jpeg", feature = "webp", feature = "bmp"))]
+mod encode;
+mod hash;
+mod homography;
+mod layout;
+mod motion;
+mod pool;
+#[cfg(feature = "qr")]
+mod qr;
+mod ring;
+mod scene;
+mod seek;
+mod sharpness;
+mod sprite;
+mod stats;
+mod temporal;
+#[cfg(feature = "stream")]
+mod stream;
+mod undistort;
 mod utils;
+#[cfg(feature = "webcodecs")]
+mod webcodecs;
+mod y4m;
 
 #[cfg(feature = "2d")]
 mod d2;
@@ -65,6 +94,29 @@ impl CaptureMode {
     pub const fn put_top_left() -> Self {
         Self::Put(0, 0)
     }
+
+    /// Map the point on the capture area of `capture_size` back to the source frame
+    /// of `source_size` drawn with the mode.
+    pub fn map_to_source(
+        self,
+        (x, y): (f64, f64),
+        (cw, ch): (u32, u32),
+        (sw, sh): (u32, u32),
+    ) -> (f64, f64) {
+        let (cw, ch, sw, sh) = (cw as f64, ch as f64, sw as f64, sh as f64);
+        match self {
+            Self::Put(dx, dy) => (x - dx as f64, y - dy as f64),
+            Self::Fill if cw > 0.0 && ch > 0.0 => (x * sw / cw, y * sh / ch),
+            Self::Fill | Self::Adjust => (x, y),
+            Self::Pinhole => {
+                let scale = (cw / sw).max(ch / sh);
+                if !scale.is_normal() {
+                    return (x, y);
+                }
+                ((x - cw / 2.0) / scale + sw / 2.0, (y - ch / 2.0) / scale + sh / 2.0)
+            }
+        }
+    }
 }
 
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
@@ -78,6 +130,27 @@ pub enum CaptureColor {
     LLLA,
 }
 
+/// Filter used to sample the video frame when it is scaled.
+///
+/// The 2D captures draw with the browser smoothing for [`Resample::Nearest`]
+/// and [`Resample::Bilinear`] and resample on the CPU for the other filters.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Resample {
+    /// Take the nearest pixel.
+    Nearest,
+    /// Interpolate between the four nearest pixels.
+    /// This is the default filter.
+    #[default]
+    Bilinear,
+    /// Average all pixels covered by the output pixel.
+    Area,
+    /// Catmull-Rom cubic interpolation.
+    Bicubic,
+    /// Lanczos windowed sinc with three lobes.
+    Lanczos,
+}
+
 pub trait CaptureArea {
     /// Get the width of the available capture area in pixels.
     fn capture_width(&self) -> u32;
@@ -108,7 +181,15 @@ pub trait CaptureArea {
     }
 }
 
-pub trait BrowserVideoCapture: CaptureArea {
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Video capture of the browser backends.
+///
+/// The trait is sealed and cannot be implemented out of the crate,
+/// so new capture methods can be added in minor releases.
+pub trait BrowserVideoCapture: CaptureArea + sealed::Sealed {
     /// Get the number of channels in the capture buffer.
     fn channels_count(&self) -> u32 {
         4
@@ -125,24 +206,156 @@ pub trait BrowserVideoCapture: CaptureArea {
         }
     }
 
-    /// Get the size of the capture buffer in bytes.
+    /// Get the size of the capture buffer in the capture layout in bytes.
     fn buffer_size(&self) -> usize {
-        (self.capture_area() * self.channels_count()) as usize
+        self.layout()
+            .buffer_size(self.capture_size(), self.channels_count())
     }
 
     /// Capture a frame from the video element.
     fn capture(&self, source: &HtmlVideoElement, mode: CaptureMode) -> (u32, u32);
 
-    /// Retrieve the grabbed frame raw data into the buffer.
+    /// Capture the quadrilateral region of the video frame rectified into
+    /// the warp output size, which then takes the place of the video size
+    /// for the capture mode.
+    fn capture_warp(&self, source: &HtmlVideoElement, warp: &Warp, mode: CaptureMode) -> (u32, u32);
+
+    /// Capture the video frame downscaled into every level of the pyramid
+    /// in one pass, the capture area is resized to hold all levels.
+    fn capture_pyramid(&self, source: &HtmlVideoElement, pyramid: &Pyramid) -> (u32, u32);
+
+    /// Capture every source into its tile of the mosaic with the tile capture mode,
+    /// the capture area is resized to hold all tiles.
+    ///
+    /// Returns the part of every tile covered by its source,
+    /// the tile is empty when the source has no frame yet.
+    fn capture_mosaic(
+        &self,
+        sources: &[(&HtmlVideoElement, CaptureMode)],
+        mosaic: &Mosaic,
+    ) -> Vec<Rect>;
+
+    /// Read the raw data of the mosaic with the part of every tile covered by its source.
+    fn read_mosaic(
+        &self,
+        sources: &[(&HtmlVideoElement, CaptureMode)],
+        mosaic: &Mosaic,
+    ) -> (Vec<u8>, Vec<Rect>) {
+        let rects = self.capture_mosaic(sources, mosaic);
+        (self.packed_data(), rects)
+    }
+
+    /// Get the filter used to sample the video frame when it is scaled.
+    fn resample(&self) -> Resample;
+
+    /// Set the filter used to sample the video frame when it is scaled.
+    fn set_resample(&self, resample: Resample);
+
+    /// Set the lens distortion correction applied to the video frame
+    /// before it is placed by the capture mode, `None` disables it.
+    fn set_undistort(&self, undistort: Option<Undistort>);
+
+    /// Get the mode of the temporal filter of the captured frames.
+    fn temporal(&self) -> Option<TemporalMode>;
+
+    /// Set the temporal filter which blends every captured frame with the history
+    /// of the previous ones, `None` disables it. Setting the mode restarts the history.
+    ///
+    /// WebGL captures without float or half-float targets keep the history on the CPU,
+    /// [`temporal`](Self::temporal) stays `None` when the filter cannot be applied.
+    fn set_temporal(&self, mode: Option<TemporalMode>);
+
+    /// Get the memory layout of the retrieved frames.
+    fn layout(&self) -> FrameLayout;
+
+    /// Set the memory layout of the retrieved frames.
+    fn set_layout(&self, layout: FrameLayout);
+
+    /// Retrieve the grabbed frame raw data in the capture layout
+    /// into the buffer of [`buffer_size`](Self::buffer_size) bytes.
     fn retrieve(&self, buffer: &mut [u8]);
 
-    /// Get the raw data from the captured frame.
+    /// Retrieve the raw data of the grabbed frame rectangle with the top-left corner
+    /// at `(x, y)` into the buffer, pixels out of the capture area are transparent black.
+    ///
+    /// The origin is the top-left corner of the capture area in all backends,
+    /// the data is tightly packed top-down RGBA regardless of the capture layout.
+    fn retrieve_rect(&self, x: i32, y: i32, width: u32, height: u32, buffer: &mut [u8]);
+
+    /// Sample the grabbed frame pixel at `(x, y)` from the top-left corner.
+    fn sample(&self, x: i32, y: i32) -> [u8; 4] {
+        let mut pixel = [0; 4];
+        let channels = self.channels_count() as usize;
+        self.retrieve_rect(x, y, 1, 1, &mut pixel[..channels]);
+        pixel
+    }
+
+    /// Get the raw data from the captured frame in the capture layout.
     fn data(&self) -> Vec<u8> {
         let mut buffer = vec![0; self.buffer_size()];
         self.retrieve(&mut buffer);
         buffer
     }
 
+    /// Compute the histograms of the captured frame, the WebGL captures
+    /// compute them on the GPU and read back only the histograms when the context
+    /// can render into float targets.
+    fn stats(&self) -> FrameStats {
+        FrameStats::from_frame(&self.packed_data(), self.channels_count())
+    }
+
+    /// Score the sharpness of the captured frame inside `rect`, or the whole frame
+    /// for `None`, to reject blurry frames. The WebGL captures score it on the GPU
+    /// when the context can render into float targets.
+    fn sharpness(&self, method: SharpnessMethod, rect: Option<Rect>) -> f64 {
+        let size = self.capture_size();
+        method.score(&self.packed_data(), size, self.channels_count(), rect)
+    }
+
+    /// Count the pixels of every cell of the `(columns, rows)` grid which luminance
+    /// changed by more than `threshold` since the previous call, row by row.
+    ///
+    /// Returns `None` when the capture cannot keep the previous frame on the GPU.
+    fn changed_cells(&self, threshold: u8, grid: (u32, u32)) -> Option<Vec<u32>> {
+        let _ = (threshold, grid);
+        None
+    }
+
+    /// Forget the previous frame kept on the GPU.
+    fn reset_motion(&self) {}
+
+    /// Update the background model kept on the GPU with the captured frame
+    /// and get its foreground mask, `255` for the foreground pixel.
+    ///
+    /// Returns `None` when the capture cannot keep the model on the GPU.
+    fn update_background(&self, options: BackgroundOptions) -> Option<Vec<u8>> {
+        let _ = options;
+        None
+    }
+
+    /// Forget the background model kept on the GPU.
+    fn reset_background(&self) {}
+
+    /// Get the tightly packed top-down RGBA data from the captured frame
+    /// regardless of the capture layout.
+    fn packed_data(&self) -> Vec<u8> {
+        let (width, height) = self.capture_size();
+        let mut buffer = vec![0; (width * height * self.channels_count()) as usize];
+        if !buffer.is_empty() {
+            self.retrieve_rect(0, 0, width, height, &mut buffer);
+        }
+        buffer
+    }
+
+    /// Get the raw data from the captured frame into the buffer taken from the pool.
+    fn data_pooled(&self, pool: &BufferPool) -> PooledBuffer {
+        let mut buffer = pool.take(self.buffer_size());
+        if !buffer.is_empty() {
+            self.retrieve(&mut buffer);
+        }
+        buffer
+    }
+
     #[cfg(feature = "image")]
     fn image(&self) -> Option<image::DynamicImage> {
         let (width, height) = self.capture_size();
@@ -150,42 +363,83 @@ pub trait BrowserVideoCapture: CaptureArea {
             1 => image::DynamicImage::ImageLuma8(image::GrayImage::from_raw(
                 width,
                 height,
-                self.data(),
+                self.packed_data(),
             )?),
             2 => image::DynamicImage::ImageLumaA8(image::GrayAlphaImage::from_raw(
                 width,
                 height,
-                self.data(),
+                self.packed_data(),
             )?),
             3 => image::DynamicImage::ImageRgb8(image::RgbImage::from_raw(
                 width,
                 height,
-                self.data(),
+                self.packed_data(),
             )?),
             4 => image::DynamicImage::ImageRgba8(image::RgbaImage::from_raw(
                 width,
                 height,
-                self.data(),
+                self.packed_data(),
             )?),
             _ => panic!("Unsupported channels count"),
         })
     }
 
-    /// Read the raw data from the video element.
+    /// Encode the captured frame into PNG.
+    #[cfg(feature = "png")]
+    fn encode_png(&self) -> image::ImageResult<Vec<u8>> {
+        encode_png(&self.packed_data(), self.capture_size(), self.color_type())
+    }
+
+    /// Encode the captured frame into JPEG with `quality` from `1` to `100`,
+    /// the alpha channel is dropped.
+    #[cfg(feature = "jpeg")]
+    fn encode_jpeg(&self, quality: u8) -> image::ImageResult<Vec<u8>> {
+        encode_jpeg(&self.packed_data(), self.capture_size(), self.color_type(), quality)
+    }
+
+    /// Encode the captured frame into lossless WebP.
+    #[cfg(feature = "webp")]
+    fn encode_webp(&self) -> image::ImageResult<Vec<u8>> {
+        encode_webp(&self.packed_data(), self.capture_size(), self.color_type())
+    }
+
+    /// Encode the captured frame into BMP.
+    #[cfg(feature = "bmp")]
+    fn encode_bmp(&self) -> image::ImageResult<Vec<u8>> {
+        encode_bmp(&self.packed_data(), self.capture_size(), self.color_type())
+    }
+
+    /// Read the raw data from the video element in the capture layout.
     fn read(&self, source: &HtmlVideoElement, mode: CaptureMode) -> Vec<u8> {
         let (width, height) = self.capture(source, mode);
 
-        let buffer_size = (width * height * self.channels_count()) as usize;
+        let buffer_size = self
+            .layout()
+            .buffer_size((width, height), self.channels_count());
 
         if buffer_size > 0 {
             let mut buffer = vec![0; buffer_size];
             self.retrieve(&mut buffer);
             buffer
         } else {
-            return Vec::new();
+            Vec::new()
         }
     }
 
+    /// Read the raw data from the video element into the buffer taken from the pool.
+    fn read_pooled(&self, source: &HtmlVideoElement, mode: CaptureMode, pool: &BufferPool) -> PooledBuffer {
+        let (width, height) = self.capture(source, mode);
+
+        let mut buffer = pool.take(
+            self.layout()
+                .buffer_size((width, height), self.channels_count()),
+        );
+        if !buffer.is_empty() {
+            self.retrieve(&mut buffer);
+        }
+        buffer
+    }
+
     /// Clear the capture area.
     fn clear(&self);
 }
@@ -230,6 +484,8 @@ pub struct BrowserCaptureBuilder {
     pub canvas: Option<SupportedCanvas>,
     pub color: Option<CaptureColor>,
     pub options: Option<SupportedOptions>,
+    pub resample: Option<Resample>,
+    pub layout: Option<FrameLayout>,
 }
 
 impl BrowserCaptureBuilder {
@@ -238,6 +494,16 @@ impl BrowserCaptureBuilder {
         self
     }
 
+    pub fn resample(mut self, resample: Resample) -> Self {
+        self.resample = Some(resample);
+        self
+    }
+
+    pub fn layout(mut self, layout: FrameLayout) -> Self {
+        self.layout = Some(layout);
+        self
+    }
+
     pub fn canvas(mut self, canvas: SupportedCanvas) -> Self {
         self.canvas = Some(canvas);
         self
@@ -284,7 +550,11 @@ impl BrowserCaptureBuilder {
     }
 
     pub fn build(self) -> Option<Result<BrowserCapture, js_sys::Error>> {
-        match (self.canvas, self.context, self.options) {
+        let resample = self.resample;
+        let layout = self.layout;
+
+        let capture: Option<Result<BrowserCapture, js_sys::Error>> =
+            match (self.canvas, self.context, self.options) {
             #[cfg(feature = "html-2d")]
             (Some(SupportedCanvas::Html(canvas)), Some(SupportedContext::Html2D(context)), _) => {
                 Some(Ok(HtmlCapture2D::new(
@@ -438,11 +708,63 @@ impl BrowserCaptureBuilder {
                 .map(Into::into),
             ),
             _ => None,
-        }
+        };
+
+        capture.map(|result| {
+            result.inspect(|capture| {
+                if let Some(resample) = resample {
+                    capture.set_resample(resample);
+                }
+                if let Some(layout) = layout {
+                    capture.set_layout(layout);
+                }
+            })
+        })
     }
 }
 
+#[cfg(any(feature = "gif", feature = "apng"))]
+pub use animation::{AnimationFrame, AnimationRecorder};
+pub use atlas::{Mosaic, Pyramid, Rect};
+pub use avi::AviWriter;
+pub use background::{BackgroundModel, BackgroundOptions};
+#[cfg(feature = "blob")]
+pub use blob::{blob_bytes, BlobFormat, BlobOptions};
+#[cfg(feature = "bmp")]
+pub use encode::encode_bmp;
+#[cfg(feature = "jpeg")]
+pub use encode::encode_jpeg;
+#[cfg(feature = "png")]
+pub use encode::encode_png;
+#[cfg(feature = "webp")]
+pub use encode::encode_webp;
+pub use hash::{HashMethod, ImageHash};
+pub use homography::{warp_rgba, warp_rgba_with, Homography, Warp};
+pub use layout::{ChannelOrder, FrameLayout, RowOrder};
+pub use motion::{Motion, MotionDetector, MotionOptions};
+pub use pool::{BufferPool, PooledBuffer};
+#[cfg(feature = "qr")]
+pub use qr::{QrCode, QrEcLevel, QrScanner};
+pub use ring::{FrameMeta, FrameRef, FrameRing};
+pub use scene::{SceneChange, SceneDetector, SceneOptions};
+pub use seek::seek_interval;
+#[cfg(feature = "seek")]
+pub use seek::{extract_frames, seek, seek_with, SeekWait};
+pub use sprite::{SpriteCue, SpriteSheet};
+pub use sharpness::{SharpestFrame, SharpnessMethod};
+pub use stats::{FrameStats, StatsChannel};
+pub use temporal::{TemporalFilter, TemporalMode};
+#[cfg(feature = "recorder")]
+pub use stream::StreamRecorder;
+#[cfg(feature = "stream")]
+pub use stream::{CanvasStream, StreamMode};
+pub use undistort::{RemapTable, Undistort};
 pub use utils::video_size;
+#[cfg(feature = "webcodecs")]
+pub use webcodecs::buffer_frame;
+#[cfg(all(feature = "webcodecs", web_sys_unstable_apis))]
+pub use webcodecs::{EncodedChunk, EncoderOptions, FrameEncoder, VideoCodec};
+pub use y4m::{frame_to_ycbcr, ycbcr_to_rgba, Y4mChroma, Y4mReader, Y4mWriter};
 
 #[cfg(all(feature = "html", feature = "2d"))]
 pub use d2::html::ColorSpaceType;
@@ -519,6 +841,78 @@ impl_enum_from!(HtmlContextOptionsGL => SupportedOptions:HtmlGL);
 #[cfg(all(feature = "offscreen", feature = "gl"))]
 impl_enum_from!(OffscreenContextOptionsGL => SupportedOptions:OffscreenGL);
 
+#[cfg(feature = "blob")]
+#[allow(unused_variables)]
+impl BrowserCapture {
+    /// Encode the captured frame with the browser image encoder.
+    pub async fn encode_blob(&self, options: BlobOptions) -> Result<web_sys::Blob, js_sys::Error> {
+        match self {
+            #[cfg(feature = "html-2d")]
+            Self::Html2D(c) => c.encode_blob(options).await,
+            #[cfg(feature = "offscreen-2d")]
+            Self::Offscreen2D(c) => c.encode_blob(options).await,
+            #[cfg(all(feature = "html", feature = "webgl"))]
+            Self::HtmlGL(c) => c.encode_blob(options).await,
+            #[cfg(all(feature = "html", feature = "webgl2"))]
+            Self::HtmlGL2(c) => c.encode_blob(options).await,
+            #[cfg(all(feature = "offscreen", feature = "webgl"))]
+            Self::OffscreenGL(c) => c.encode_blob(options).await,
+            #[cfg(all(feature = "offscreen", feature = "webgl2"))]
+            Self::OffscreenGL2(c) => c.encode_blob(options).await,
+            #[allow(unreachable_patterns)]
+            _ => panic!("Unsupported variant. Please enable any features."),
+        }
+    }
+
+    /// Encode the captured frame with the browser image encoder into bytes.
+    pub async fn encode_bytes(&self, options: BlobOptions) -> Result<Vec<u8>, js_sys::Error> {
+        blob_bytes(&self.encode_blob(options).await?).await
+    }
+}
+
+#[cfg(feature = "webcodecs")]
+#[allow(unused_variables)]
+impl BrowserCapture {
+    /// Wrap the captured frame into the video frame with `timestamp` in seconds.
+    pub fn video_frame(&self, timestamp: f64) -> Result<web_sys::VideoFrame, js_sys::Error> {
+        match self {
+            #[cfg(feature = "html-2d")]
+            Self::Html2D(c) => c.video_frame(timestamp),
+            #[cfg(feature = "offscreen-2d")]
+            Self::Offscreen2D(c) => c.video_frame(timestamp),
+            #[cfg(all(feature = "html", feature = "webgl"))]
+            Self::HtmlGL(c) => c.video_frame(timestamp),
+            #[cfg(all(feature = "html", feature = "webgl2"))]
+            Self::HtmlGL2(c) => c.video_frame(timestamp),
+            #[cfg(all(feature = "offscreen", feature = "webgl"))]
+            Self::OffscreenGL(c) => c.video_frame(timestamp),
+            #[cfg(all(feature = "offscreen", feature = "webgl2"))]
+            Self::OffscreenGL2(c) => c.video_frame(timestamp),
+            #[allow(unreachable_patterns)]
+            _ => panic!("Unsupported variant. Please enable any features."),
+        }
+    }
+}
+
+#[cfg(feature = "stream")]
+#[allow(unused_variables)]
+impl BrowserCapture {
+    /// Capture the canvas content into the media stream,
+    /// only HTML canvas captures support it.
+    pub fn capture_stream(&self, mode: StreamMode) -> Result<CanvasStream, js_sys::Error> {
+        match self {
+            #[cfg(feature = "html-2d")]
+            Self::Html2D(c) => c.capture_stream(mode),
+            #[cfg(all(feature = "html", feature = "webgl"))]
+            Self::HtmlGL(c) => c.capture_stream(mode),
+            #[cfg(all(feature = "html", feature = "webgl2"))]
+            Self::HtmlGL2(c) => c.capture_stream(mode),
+            #[allow(unreachable_patterns)]
+            _ => Err(js_sys::Error::new("Capture stream requires the HTML canvas")),
+        }
+    }
+}
+
 impl From<BrowserCapture> for Box<dyn BrowserVideoCapture> {
     fn from(value: BrowserCapture) -> Self {
         match value {
@@ -546,15 +940,47 @@ impl CaptureArea for BrowserCapture {
     enum_method!(set_capture_height (height: u32) => ());
 }
 
+impl sealed::Sealed for BrowserCapture {}
+
 #[allow(unused_variables)]
 impl BrowserVideoCapture for BrowserCapture {
     enum_method!(channels_count () => u32);
     enum_method!(buffer_size () => usize);
     enum_method!(capture (source: &HtmlVideoElement, mode: CaptureMode) => (u32, u32));
+    enum_method!(capture_warp (source: &HtmlVideoElement, warp: &Warp, mode: CaptureMode) => (u32, u32));
+    enum_method!(capture_pyramid (source: &HtmlVideoElement, pyramid: &Pyramid) => (u32, u32));
+    enum_method!(capture_mosaic (sources: &[(&HtmlVideoElement, CaptureMode)], mosaic: &Mosaic) => Vec<Rect>);
+    enum_method!(read_mosaic (sources: &[(&HtmlVideoElement, CaptureMode)], mosaic: &Mosaic) => (Vec<u8>, Vec<Rect>));
+    enum_method!(resample () => Resample);
+    enum_method!(set_resample (resample: Resample) => ());
+    enum_method!(set_undistort (undistort: Option<Undistort>) => ());
+    enum_method!(temporal () => Option<TemporalMode>);
+    enum_method!(set_temporal (mode: Option<TemporalMode>) => ());
+    enum_method!(layout () => FrameLayout);
+    enum_method!(set_layout (layout: FrameLayout) => ());
     enum_method!(retrieve (buffer: &mut [u8]) => ());
+    enum_method!(retrieve_rect (x: i32, y: i32, width: u32, height: u32, buffer: &mut [u8]) => ());
+    enum_method!(sample (x: i32, y: i32) => [u8; 4]);
     enum_method!(data () => Vec<u8>);
+    enum_method!(packed_data () => Vec<u8>);
+    enum_method!(stats () => FrameStats);
+    enum_method!(sharpness (method: SharpnessMethod, rect: Option<Rect>) => f64);
+    enum_method!(changed_cells (threshold: u8, grid: (u32, u32)) => Option<Vec<u32>>);
+    enum_method!(reset_motion () => ());
+    enum_method!(update_background (options: BackgroundOptions) => Option<Vec<u8>>);
+    enum_method!(reset_background () => ());
+    enum_method!(data_pooled (pool: &BufferPool) => PooledBuffer);
     #[cfg(feature = "image")]
     enum_method!(image () => Option<image::DynamicImage>);
+    #[cfg(feature = "png")]
+    enum_method!(encode_png () => image::ImageResult<Vec<u8>>);
+    #[cfg(feature = "jpeg")]
+    enum_method!(encode_jpeg (quality: u8) => image::ImageResult<Vec<u8>>);
+    #[cfg(feature = "webp")]
+    enum_method!(encode_webp () => image::ImageResult<Vec<u8>>);
+    #[cfg(feature = "bmp")]
+    enum_method!(encode_bmp () => image::ImageResult<Vec<u8>>);
     enum_method!(read (source: &HtmlVideoElement, mode: CaptureMode) => Vec<u8>);
+    enum_method!(read_pooled (source: &HtmlVideoElement, mode: CaptureMode, pool: &BufferPool) => PooledBuffer);
     enum_method!(clear () => ());
 }
@@ -15,7 +15,7 @@ macro_rules! js_set {
 #[macro_export]
 macro_rules! impl_canvas_capture_area {
     ($name:ty) => {
-        impl crate::CaptureArea for $name {
+        impl $crate::CaptureArea for $name {
             fn capture_width(&self) -> u32 {
                 self.canvas.width()
             }
@@ -35,6 +35,22 @@ macro_rules! impl_canvas_capture_area {
     };
 }
 
+#[macro_export]
+macro_rules! impl_canvas_stream {
+    ($name:ty) => {
+        impl $name {
+            /// Capture the canvas content into the media stream,
+            /// which holds the video after the color conversion and filtering.
+            pub fn capture_stream(
+                &self,
+                mode: $crate::StreamMode,
+            ) -> Result<$crate::CanvasStream, js_sys::Error> {
+                $crate::CanvasStream::new(&self.canvas, mode)
+            }
+        }
+    };
+}
+
 #[macro_export]
 macro_rules! get_context {
     ("2d" $name:literal, $canvas:expr, $options:expr) => {
@@ -64,7 +80,7 @@ macro_rules! get_context {
 macro_rules! impl_capture_from_canvas {
     ($id:tt, $capture:ty, $canvas:ty, $context:ty, $option:ty) => {
         impl $capture {
-            pub fn from_canvas(canvas: $canvas, color: crate::CaptureColor) -> Result<Option<Self>, js_sys::Error> {
+            pub fn from_canvas(canvas: $canvas, color: $crate::CaptureColor) -> Result<Option<Self>, js_sys::Error> {
                 canvas
                     .get_context($id)
                     .map(|value| {
@@ -77,7 +93,7 @@ macro_rules! impl_capture_from_canvas {
 
             pub fn from_canvas_with_options(
                 canvas: $canvas,
-                color: crate::CaptureColor,
+                color: $crate::CaptureColor,
                 options: $option,
             ) -> Result<Option<Self>, js_sys::Error> {
                 get_context!($id $id, canvas, options)
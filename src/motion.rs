@@ -0,0 +1,258 @@
+use crate::{stats::luma, BrowserVideoCapture, Rect};
+
+/// Options of the motion detector.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MotionOptions {
+    /// Minimal luminance difference of the changed pixel.
+    pub threshold: u8,
+    /// Number of the grid columns and rows.
+    pub grid: (u32, u32),
+    /// Minimal fraction of changed pixels of the moving cell.
+    pub sensitivity: f64,
+}
+
+impl Default for MotionOptions {
+    fn default() -> Self {
+        Self {
+            threshold: 24,
+            grid: (16, 9),
+            sensitivity: 0.05,
+        }
+    }
+}
+
+impl MotionOptions {
+    pub fn threshold(mut self, value: u8) -> Self {
+        self.threshold = value;
+        self
+    }
+
+    pub fn grid(mut self, columns: u32, rows: u32) -> Self {
+        self.grid = (columns.max(1), rows.max(1));
+        self
+    }
+
+    pub fn sensitivity(mut self, value: f64) -> Self {
+        self.sensitivity = value;
+        self
+    }
+}
+
+/// Motion between two frames.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Motion {
+    /// Fraction of changed pixels of the whole frame.
+    pub ratio: f64,
+    /// Fraction of changed pixels of every grid cell row by row.
+    pub cells: Vec<f64>,
+    /// Bounding boxes of the connected moving cells.
+    pub regions: Vec<Rect>,
+}
+
+impl Motion {
+    /// Check if any cell moves.
+    pub fn is_moving(&self) -> bool {
+        !self.regions.is_empty()
+    }
+}
+
+/// Motion detector comparing every frame with the previous one.
+///
+/// WebGL captures keep the previous frame in the texture and read back
+/// only the grid, other captures keep it in the CPU buffer. Detectors sharing
+/// the WebGL capture share its previous frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MotionDetector {
+    options: MotionOptions,
+    /// Sensitivity of every grid cell row by row.
+    cells: Vec<f64>,
+    size: (u32, u32),
+    previous: Vec<u8>,
+    mask: Vec<u8>,
+    frame: Vec<u8>,
+    /// The previous frame kept by the capture should be forgotten too.
+    reset: bool,
+}
+
+impl MotionDetector {
+    pub fn new(options: MotionOptions) -> Self {
+        let (columns, rows) = options.grid;
+        let options = options.grid(columns, rows);
+        Self {
+            options,
+            cells: vec![options.sensitivity; (options.grid.0 * options.grid.1) as usize],
+            size: (0, 0),
+            previous: Vec::new(),
+            mask: Vec::new(),
+            frame: Vec::new(),
+            reset: true,
+        }
+    }
+
+    pub fn options(&self) -> &MotionOptions {
+        &self.options
+    }
+
+    /// Set the sensitivity of the grid cell, cells with the sensitivity
+    /// above `1` never move.
+    pub fn set_cell_sensitivity(&mut self, column: u32, row: u32, sensitivity: f64) {
+        let (columns, rows) = self.options.grid;
+        if column < columns && row < rows {
+            self.cells[(row * columns + column) as usize] = sensitivity;
+        }
+    }
+
+    /// Get the thresholded difference mask of the last frame compared on the CPU,
+    /// `255` for the changed pixel.
+    pub fn mask(&self) -> &[u8] {
+        &self.mask
+    }
+
+    /// Forget the previous frame.
+    pub fn reset(&mut self) {
+        self.size = (0, 0);
+        self.previous.clear();
+        self.mask.clear();
+        self.reset = true;
+    }
+
+    /// Compare the raw frame with `channels` bytes per pixel with the previous one,
+    /// the first frame and the frame of the new size have no motion.
+    pub fn update(&mut self, buffer: &[u8], size: (u32, u32), channels: u32) -> Motion {
+        let (width, height) = size;
+        let (columns, rows) = self.options.grid;
+        let area = (width * height) as usize;
+
+        let first = self.size != size;
+        self.size = size;
+        self.previous.resize(area, 0);
+        self.mask.resize(area, 0);
+
+        let mut counts = vec![0; (columns * rows) as usize];
+        let pixels = buffer.chunks_exact(channels as usize).take(area);
+        for (index, pixel) in pixels.enumerate() {
+            let value = match *pixel {
+                [l] | [l, _] => l,
+                [r, g, b, ..] => luma(r, g, b),
+                [] => unreachable!(),
+            };
+
+            let changed = !first && value.abs_diff(self.previous[index]) > self.options.threshold;
+            self.previous[index] = value;
+            self.mask[index] = if changed { 255 } else { 0 };
+
+            if changed {
+                let (x, y) = (index as u32 % width, index as u32 / width);
+                counts[(y * rows / height * columns + x * columns / width) as usize] += 1;
+            }
+        }
+
+        self.motion(size, &counts)
+    }
+
+    /// Capture the frame difference of the capture.
+    pub fn detect(&mut self, capture: &(impl BrowserVideoCapture + ?Sized)) -> Motion {
+        let size = capture.capture_size();
+        let (threshold, grid) = (self.options.threshold, self.options.grid);
+
+        if std::mem::take(&mut self.reset) {
+            capture.reset_motion();
+        }
+        if let Some(counts) = capture.changed_cells(threshold, grid) {
+            self.mask.clear();
+            return self.motion(size, &counts);
+        }
+
+        let channels = capture.channels_count();
+        let mut frame = std::mem::take(&mut self.frame);
+        frame.resize((size.0 * size.1 * channels) as usize, 0);
+        if !frame.is_empty() {
+            capture.retrieve_rect(0, 0, size.0, size.1, &mut frame);
+        }
+
+        let motion = self.update(&frame, size, channels);
+        self.frame = frame;
+        motion
+    }
+
+    /// Get the motion from the numbers of changed pixels of the grid cells.
+    fn motion(&self, (width, height): (u32, u32), counts: &[u32]) -> Motion {
+        let (columns, rows) = self.options.grid;
+        let cell = |column: u32, row: u32| {
+            let (x, y) = (column * width / columns, row * height / rows);
+            Rect::new(
+                x,
+                y,
+                (column + 1) * width / columns - x,
+                (row + 1) * height / rows - y,
+            )
+        };
+
+        let mut cells = Vec::with_capacity(counts.len());
+        for (index, &count) in counts.iter().enumerate() {
+            let area = cell(index as u32 % columns, index as u32 / columns).area();
+            cells.push(if area > 0 {
+                count as f64 / area as f64
+            } else {
+                0.0
+            });
+        }
+
+        let area = width as u64 * height as u64;
+        let changed: u64 = counts.iter().map(|&count| count as u64).sum();
+        let ratio = if area > 0 {
+            changed as f64 / area as f64
+        } else {
+            0.0
+        };
+
+        // group the neighboring moving cells into regions
+        let moving: Vec<bool> = cells
+            .iter()
+            .zip(&self.cells)
+            .map(|(ratio, sensitivity)| *ratio > 0.0 && ratio >= sensitivity)
+            .collect();
+        let mut visited = vec![false; moving.len()];
+        let mut regions = Vec::new();
+
+        for start in 0..moving.len() {
+            if !moving[start] || visited[start] {
+                continue;
+            }
+
+            visited[start] = true;
+            let mut stack = vec![start];
+            let (mut left, mut top, mut right, mut bottom) = (u32::MAX, u32::MAX, 0, 0);
+
+            while let Some(index) = stack.pop() {
+                let (column, row) = (index as u32 % columns, index as u32 / columns);
+                let rect = cell(column, row);
+                left = left.min(rect.x);
+                top = top.min(rect.y);
+                right = right.max(rect.x + rect.width);
+                bottom = bottom.max(rect.y + rect.height);
+
+                let neighbors = [
+                    (column > 0).then(|| index - 1),
+                    (column + 1 < columns).then(|| index + 1),
+                    (row > 0).then(|| index - columns as usize),
+                    (row + 1 < rows).then(|| index + columns as usize),
+                ];
+                for neighbor in neighbors.iter().flatten() {
+                    if moving[*neighbor] && !visited[*neighbor] {
+                        visited[*neighbor] = true;
+                        stack.push(*neighbor);
+                    }
+                }
+            }
+
+            regions.push(Rect::new(left, top, right - left, bottom - top));
+        }
+
+        Motion {
+            ratio,
+            cells,
+            regions,
+        }
+    }
+}
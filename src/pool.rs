@@ -0,0 +1,118 @@
+use std::{
+    cell::RefCell,
+    ops::{Deref, DerefMut},
+    rc::{Rc, Weak},
+};
+
+type FreeList = RefCell<Vec<Vec<u8>>>;
+
+/// Pool of reusable frame buffers.
+///
+/// Buffers taken from the pool go back to it on drop, so reading frames
+/// in a loop does not allocate once the pool is warm.
+#[derive(Debug, Clone)]
+pub struct BufferPool {
+    free: Rc<FreeList>,
+    limit: usize,
+}
+
+impl Default for BufferPool {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_LIMIT)
+    }
+}
+
+impl BufferPool {
+    /// Default number of kept free buffers.
+    pub const DEFAULT_LIMIT: usize = 4;
+
+    /// Create the pool keeping at most `limit` free buffers.
+    pub fn new(limit: usize) -> Self {
+        Self {
+            free: Rc::new(RefCell::new(Vec::with_capacity(limit))),
+            limit,
+        }
+    }
+
+    /// Get the number of free buffers.
+    pub fn available(&self) -> usize {
+        self.free.borrow().len()
+    }
+
+    /// Take the buffer of `size` bytes, the content of the reused buffer is unspecified.
+    pub fn take(&self, size: usize) -> PooledBuffer {
+        let mut buffer = {
+            let mut free = self.free.borrow_mut();
+            // prefer the buffer which does not need to grow
+            match free.iter().rposition(|buffer| buffer.capacity() >= size) {
+                Some(index) => free.swap_remove(index),
+                None => free.pop().unwrap_or_default(),
+            }
+        };
+        buffer.resize(size, 0);
+
+        PooledBuffer {
+            buffer,
+            pool: Rc::downgrade(&self.free),
+            limit: self.limit,
+        }
+    }
+
+    /// Drop all free buffers.
+    pub fn clear(&self) {
+        self.free.borrow_mut().clear();
+    }
+}
+
+/// Frame buffer which returns to its pool on drop.
+#[derive(Debug)]
+pub struct PooledBuffer {
+    buffer: Vec<u8>,
+    pool: Weak<FreeList>,
+    limit: usize,
+}
+
+impl PooledBuffer {
+    /// Detach the buffer from the pool.
+    pub fn into_vec(mut self) -> Vec<u8> {
+        self.pool = Weak::new();
+        std::mem::take(&mut self.buffer)
+    }
+}
+
+impl Deref for PooledBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.buffer
+    }
+}
+
+impl DerefMut for PooledBuffer {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.buffer
+    }
+}
+
+impl AsRef<[u8]> for PooledBuffer {
+    fn as_ref(&self) -> &[u8] {
+        &self.buffer
+    }
+}
+
+impl AsMut<[u8]> for PooledBuffer {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.buffer
+    }
+}
+
+impl Drop for PooledBuffer {
+    fn drop(&mut self) {
+        if let Some(pool) = self.pool.upgrade() {
+            let mut free = pool.borrow_mut();
+            if free.len() < self.limit {
+                free.push(std::mem::take(&mut self.buffer));
+            }
+        }
+    }
+}
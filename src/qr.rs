@@ -0,0 +1,991 @@
+use crate::{stats::luma, BrowserVideoCapture, CaptureMode, Homography};
+
+/// Error correction level of the QR code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QrEcLevel {
+    Low,
+    Medium,
+    Quartile,
+    High,
+}
+
+/// Decoded QR code.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QrCode {
+    pub version: u32,
+    pub ec_level: QrEcLevel,
+    /// Bytes of all data segments.
+    pub payload: Vec<u8>,
+    /// Outer corners of the symbol clockwise from its top-left corner in pixels.
+    pub corners: [(f64, f64); 4],
+}
+
+impl QrCode {
+    /// Get the payload as text replacing invalid UTF-8.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.payload).into_owned()
+    }
+}
+
+/// QR code scanner of the grayscale frames.
+///
+/// Frames are binarized with the global Otsu threshold, so captures
+/// with [`CaptureColor::LLLA`](crate::CaptureColor::LLLA) or one channel
+/// avoid the color conversion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QrScanner {
+    /// Maximal number of codes decoded in one frame.
+    pub max_codes: usize,
+}
+
+impl Default for QrScanner {
+    fn default() -> Self {
+        Self { max_codes: 4 }
+    }
+}
+
+impl QrScanner {
+    pub fn max_codes(mut self, value: usize) -> Self {
+        self.max_codes = value;
+        self
+    }
+
+    /// Decode the QR codes of the raw frame with `channels` bytes per pixel,
+    /// the corners are in the frame pixels.
+    pub fn scan(&self, buffer: &[u8], size: (u32, u32), channels: u32) -> Vec<QrCode> {
+        let Some(image) = Binary::new(buffer, size, channels) else {
+            return Vec::new();
+        };
+
+        let finders = image.finders();
+        let mut used = vec![false; finders.len()];
+        let mut codes = Vec::new();
+
+        for (a, b, c) in triples(&finders) {
+            if codes.len() >= self.max_codes {
+                break;
+            }
+            if used[a] || used[b] || used[c] {
+                continue;
+            }
+            if let Some(code) = image.decode([&finders[a], &finders[b], &finders[c]]) {
+                used[a] = true;
+                used[b] = true;
+                used[c] = true;
+                codes.push(code);
+            }
+        }
+
+        codes
+    }
+
+    /// Decode the QR codes of the captured frame of `source` drawn with `mode`,
+    /// the corners are mapped back to the video pixels.
+    pub fn scan_capture(
+        &self,
+        capture: &(impl BrowserVideoCapture + ?Sized),
+        source: &web_sys::HtmlVideoElement,
+        mode: CaptureMode,
+    ) -> Vec<QrCode> {
+        let size = capture.capture_size();
+        let source_size = crate::utils::video_size(source);
+        let mut codes = self.scan(&capture.packed_data(), size, capture.channels_count());
+
+        for code in &mut codes {
+            for corner in &mut code.corners {
+                *corner = mode.map_to_source(*corner, size, source_size);
+            }
+        }
+        codes
+    }
+}
+
+/// Center of the finder pattern.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Finder {
+    x: f64,
+    y: f64,
+    module: f64,
+    count: u32,
+}
+
+/// Get the candidate triples of the finders from the most regular ones.
+fn triples(finders: &[Finder]) -> Vec<(usize, usize, usize)> {
+    let count = finders.len().min(16);
+    let mut triples = Vec::new();
+
+    for a in 0..count {
+        for b in a + 1..count {
+            for c in b + 1..count {
+                let modules = [finders[a].module, finders[b].module, finders[c].module];
+                let max = modules.iter().cloned().fold(f64::MIN, f64::max);
+                let min = modules.iter().cloned().fold(f64::MAX, f64::min);
+                if max > min * 1.8 {
+                    continue;
+                }
+
+                let [corner, p, q] = orient([&finders[a], &finders[b], &finders[c]]);
+                let (ux, uy) = (p.x - corner.x, p.y - corner.y);
+                let (vx, vy) = (q.x - corner.x, q.y - corner.y);
+                let (u, v) = (ux.hypot(uy), vx.hypot(vy));
+                let cos = (ux * vx + uy * vy) / (u * v);
+                let ratio = u.max(v) / u.min(v);
+                if cos.abs() < 0.4 && ratio < 1.6 && u.min(v) > 7.0 * max {
+                    triples.push((cos.abs() + ratio - 1.0, (a, b, c)));
+                }
+            }
+        }
+    }
+
+    triples.sort_by(|a, b| a.0.total_cmp(&b.0));
+    triples.into_iter().map(|(_, triple)| triple).collect()
+}
+
+/// Order the finders as the top-left, top-right and bottom-left ones.
+fn orient(finders: [&Finder; 3]) -> [&Finder; 3] {
+    let distance = |a: &Finder, b: &Finder| (a.x - b.x).hypot(a.y - b.y);
+    let [a, b, c] = finders;
+
+    // the top-left finder is opposite to the longest side
+    let (ab, bc, ca) = (distance(a, b), distance(b, c), distance(c, a));
+    let [corner, p, q] = if bc >= ab && bc >= ca {
+        [a, b, c]
+    } else if ca >= ab {
+        [b, c, a]
+    } else {
+        [c, a, b]
+    };
+
+    let cross = (p.x - corner.x) * (q.y - corner.y) - (p.y - corner.y) * (q.x - corner.x);
+    if cross > 0.0 {
+        [corner, p, q]
+    } else {
+        [corner, q, p]
+    }
+}
+
+/// Binarized frame, `true` for dark pixels.
+struct Binary {
+    width: usize,
+    height: usize,
+    pixels: Vec<bool>,
+}
+
+impl Binary {
+    fn new(buffer: &[u8], (width, height): (u32, u32), channels: u32) -> Option<Self> {
+        let area = width as usize * height as usize;
+        let length = area.checked_mul(channels as usize)?;
+        if channels == 0 || area == 0 || buffer.len() < length {
+            return None;
+        }
+
+        let values: Vec<u8> = buffer
+            .chunks_exact(channels as usize)
+            .take(area)
+            .map(|pixel| match *pixel {
+                [l] | [l, _] => l,
+                [r, g, b, ..] => luma(r, g, b),
+                [] => unreachable!(),
+            })
+            .collect();
+
+        let mut histogram = [0u64; 256];
+        values
+            .iter()
+            .for_each(|&value| histogram[value as usize] += 1);
+        let threshold = otsu(&histogram);
+
+        Some(Self {
+            width: width as usize,
+            height: height as usize,
+            pixels: values.iter().map(|&value| value <= threshold).collect(),
+        })
+    }
+
+    fn dark(&self, x: i64, y: i64) -> Option<bool> {
+        let inside = x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height;
+        inside.then(|| self.pixels[y as usize * self.width + x as usize])
+    }
+
+    /// Count the runs of the dark center and the light and dark rings around `(x, y)`
+    /// along the direction, get the center offset and the runs.
+    fn runs(&self, x: i64, y: i64, (dx, dy): (i64, i64), rings: usize) -> Option<(f64, Vec<u32>)> {
+        if self.dark(x, y) != Some(true) {
+            return None;
+        }
+
+        let mut runs = vec![0; 2 * rings + 1];
+        let mut ends = [0, 0];
+        for (side, step) in [-1, 1].iter().enumerate() {
+            let mut i = if *step < 0 { 0 } else { 1 };
+            for ring in 0..=rings {
+                let dark = ring % 2 == 0;
+                let index = if *step < 0 {
+                    rings - ring
+                } else {
+                    rings + ring
+                };
+                while self.dark(x + i * dx, y + i * dy) == Some(dark) {
+                    runs[index] += 1;
+                    i += step;
+                }
+                if ring == 0 {
+                    ends[side] = i;
+                }
+            }
+        }
+
+        let center = (ends[0] + 1 + ends[1] - 1) as f64 / 2.0;
+        Some((center, runs))
+    }
+
+    /// Check the runs of the finder pattern `1:1:3:1:1` around `(x, y)`.
+    fn finder_runs(&self, x: i64, y: i64, direction: (i64, i64)) -> Option<(f64, f64)> {
+        let (center, runs) = self.runs(x, y, direction, 2)?;
+        let total: u32 = runs.iter().sum();
+        let module = total as f64 / 7.0;
+        let expected = [1.0, 1.0, 3.0, 1.0, 1.0];
+        let valid = module >= 1.0
+            && runs.iter().zip(expected).all(|(&run, modules)| {
+                (run as f64 - modules * module).abs() < modules * module * 0.6
+            });
+        valid.then_some((center, module))
+    }
+
+    /// Find the centers of the finder patterns.
+    fn finders(&self) -> Vec<Finder> {
+        let mut finders: Vec<Finder> = Vec::new();
+
+        for y in 0..self.height {
+            // run lengths of the row starting from the first dark run
+            let row = &self.pixels[y * self.width..(y + 1) * self.width];
+            let mut runs: Vec<(usize, usize)> = Vec::new();
+            let mut start = 0;
+            for x in 1..=row.len() {
+                if x == row.len() || row[x] != row[start] {
+                    if row[start] || !runs.is_empty() {
+                        runs.push((start, x - start));
+                    }
+                    start = x;
+                }
+            }
+
+            for window in runs.windows(5).step_by(2) {
+                let total: usize = window.iter().map(|run| run.1).sum();
+                let module = total as f64 / 7.0;
+                let expected = [1.0, 1.0, 3.0, 1.0, 1.0];
+                let valid = window.iter().zip(expected).all(|(run, modules)| {
+                    (run.1 as f64 - modules * module).abs() < modules * module * 0.6
+                });
+                if !valid {
+                    continue;
+                }
+
+                let x = (window[2].0 + window[2].1 / 2) as i64;
+                let Some((dy, vertical)) = self.finder_runs(x, y as i64, (0, 1)) else {
+                    continue;
+                };
+                let cy = (y as f64 + dy).round() as i64;
+                let Some((dx, horizontal)) = self.finder_runs(x, cy, (1, 0)) else {
+                    continue;
+                };
+
+                let candidate = Finder {
+                    x: x as f64 + dx + 0.5,
+                    y: y as f64 + dy + 0.5,
+                    module: (vertical + horizontal) / 2.0,
+                    count: 1,
+                };
+                let same = finders.iter_mut().find(|finder| {
+                    (finder.x - candidate.x).hypot(finder.y - candidate.y) < 2.0 * finder.module
+                        && (finder.module - candidate.module).abs() < finder.module * 0.5
+                });
+                match same {
+                    Some(finder) => {
+                        let count = finder.count as f64;
+                        finder.x = (finder.x * count + candidate.x) / (count + 1.0);
+                        finder.y = (finder.y * count + candidate.y) / (count + 1.0);
+                        finder.module = (finder.module * count + candidate.module) / (count + 1.0);
+                        finder.count += 1;
+                    }
+                    None => finders.push(candidate),
+                }
+            }
+        }
+
+        // finders hit by one row only are noise
+        finders.retain(|finder| finder.count >= 2 || finder.module < 2.0);
+        finders.sort_by_key(|finder| std::cmp::Reverse(finder.count));
+        finders
+    }
+
+    /// Find the center of the alignment pattern `1:1:1` near `(x, y)`.
+    fn alignment(&self, (x, y): (f64, f64), module: f64) -> Option<(f64, f64)> {
+        let check = |x: i64, y: i64, direction| {
+            let (center, runs) = self.runs(x, y, direction, 1)?;
+            let valid = runs
+                .iter()
+                .all(|&run| (run as f64 - module).abs() < module * 0.7);
+            valid.then_some(center)
+        };
+
+        let radius = (4.0 * module).ceil() as i64;
+        let (x, y) = (x.floor() as i64, y.floor() as i64);
+        for ring in 0..=radius {
+            for dy in -ring..=ring {
+                for dx in -ring..=ring {
+                    if dx.abs() != ring && dy.abs() != ring {
+                        continue;
+                    }
+                    let (px, py) = (x + dx, y + dy);
+                    let Some(oy) = check(px, py, (0, 1)) else {
+                        continue;
+                    };
+                    let cy = (py as f64 + oy).round() as i64;
+                    if let Some(ox) = check(px, cy, (1, 0)) {
+                        return Some((px as f64 + ox + 0.5, py as f64 + oy + 0.5));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Sample and decode the symbol of the finders.
+    fn decode(&self, finders: [&Finder; 3]) -> Option<QrCode> {
+        let [corner, right, bottom] = orient(finders);
+        let module = (corner.module + right.module + bottom.module) / 3.0;
+        let distance = |a: &Finder, b: &Finder| (a.x - b.x).hypot(a.y - b.y);
+        let modules = (distance(corner, right) + distance(corner, bottom)) / 2.0 / module + 7.0;
+        let estimate = ((modules - 17.0) / 4.0).round().clamp(1.0, 40.0) as u32;
+
+        let mut versions = vec![estimate];
+        versions.extend(
+            [estimate.saturating_sub(1), estimate + 1]
+                .iter()
+                .filter(|v| (1..=40).contains(*v)),
+        );
+        for version in versions {
+            let size = 17 + 4 * version as usize;
+            let Some(grid) = self.sample(corner, right, bottom, size, module) else {
+                continue;
+            };
+
+            // larger versions tell their version
+            let version = match version {
+                7.. => match grid.version() {
+                    Some(read) if read != version => {
+                        let size = 17 + 4 * read as usize;
+                        let grid = self.sample(corner, right, bottom, size, module)?;
+                        return grid.decode(read);
+                    }
+                    _ => version,
+                },
+                _ => version,
+            };
+            if let Some(code) = grid.decode(version) {
+                return Some(code);
+            }
+        }
+        None
+    }
+
+    /// Sample the modules of the symbol of `size` modules.
+    fn sample(
+        &self,
+        corner: &Finder,
+        right: &Finder,
+        bottom: &Finder,
+        size: usize,
+        module: f64,
+    ) -> Option<Grid> {
+        let far = size as f64 - 3.5;
+        let mut from = [(3.5, 3.5), (far, 3.5), (3.5, far), (far, far)];
+        let mut to = [
+            (corner.x, corner.y),
+            (right.x, right.y),
+            (bottom.x, bottom.y),
+            (right.x + bottom.x - corner.x, right.y + bottom.y - corner.y),
+        ];
+
+        // the bottom-right alignment pattern corrects the perspective
+        if size > 21 {
+            let affine = Homography::from_points(from, to)?;
+            let center = size as f64 - 6.5;
+            if let Some(found) = affine
+                .map(center, center)
+                .and_then(|point| self.alignment(point, module))
+            {
+                from[3] = (center, center);
+                to[3] = found;
+            }
+        }
+
+        let homography = Homography::from_points(from, to)?;
+        let mut modules = vec![false; size * size];
+        for (index, module) in modules.iter_mut().enumerate() {
+            let (x, y) = ((index % size) as f64 + 0.5, (index / size) as f64 + 0.5);
+            let (px, py) = homography.map(x, y)?;
+            *module = self.dark(px.floor() as i64, py.floor() as i64) == Some(true);
+        }
+
+        let s = size as f64;
+        let mut corners = [(0.0, 0.0); 4];
+        for (corner, (x, y)) in corners
+            .iter_mut()
+            .zip([(0.0, 0.0), (s, 0.0), (s, s), (0.0, s)])
+        {
+            *corner = homography.map(x, y)?;
+        }
+
+        Some(Grid {
+            size,
+            modules,
+            corners,
+        })
+    }
+}
+
+/// Get the Otsu threshold of the histogram, values up to it are dark.
+fn otsu(histogram: &[u64; 256]) -> u8 {
+    let total: u64 = histogram.iter().sum();
+    let sum: f64 = histogram
+        .iter()
+        .enumerate()
+        .map(|(value, &count)| value as f64 * count as f64)
+        .sum();
+
+    let (mut background, mut background_sum) = (0u64, 0.0);
+    let (mut best, mut threshold) = (-1.0, 0);
+    for (value, &count) in histogram.iter().enumerate() {
+        background += count;
+        background_sum += value as f64 * count as f64;
+        let foreground = total - background;
+        if background == 0 || foreground == 0 {
+            continue;
+        }
+
+        let mean_background = background_sum / background as f64;
+        let mean_foreground = (sum - background_sum) / foreground as f64;
+        let variance =
+            background as f64 * foreground as f64 * (mean_background - mean_foreground).powi(2);
+        if variance > best {
+            best = variance;
+            threshold = value as u8;
+        }
+    }
+    threshold
+}
+
+/// Sampled modules of the symbol, `true` for dark modules.
+struct Grid {
+    size: usize,
+    modules: Vec<bool>,
+    corners: [(f64, f64); 4],
+}
+
+impl Grid {
+    fn get(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    /// Read the bits of the positions, the first position is the lowest bit.
+    fn bits(&self, positions: impl Iterator<Item = (usize, usize)>) -> u32 {
+        positions
+            .enumerate()
+            .fold(0, |bits, (i, (x, y))| bits | (self.get(x, y) as u32) << i)
+    }
+
+    /// Read the version information of the versions from `7`.
+    fn version(&self) -> Option<u32> {
+        let size = self.size;
+        let first = self.bits((0..18).map(|i| (size - 11 + i % 3, i / 3)));
+        let second = self.bits((0..18).map(|i| (i / 3, size - 11 + i % 3)));
+
+        (7..=40)
+            .map(|version| {
+                let bits = version << 12 | bch(version, 0x1F25, 12);
+                let distance = (bits ^ first)
+                    .count_ones()
+                    .min((bits ^ second).count_ones());
+                (distance, version)
+            })
+            .min()
+            .filter(|(distance, _)| *distance <= 3)
+            .map(|(_, version)| version)
+    }
+
+    /// Read the error correction level and the mask.
+    fn format(&self) -> Option<(QrEcLevel, u32)> {
+        let size = self.size;
+        let first = self.bits(
+            (0..6)
+                .map(|i| (8, i))
+                .chain([(8, 7), (8, 8), (7, 8)])
+                .chain((9..15).map(|i| (14 - i, 8))),
+        );
+        let second = self.bits(
+            (0..8)
+                .map(|i| (size - 1 - i, 8))
+                .chain((8..15).map(|i| (8, size - 15 + i))),
+        );
+
+        let (distance, data) = (0..32)
+            .map(|data| {
+                let bits = (data << 10 | bch(data, 0x537, 10)) ^ 0x5412;
+                let distance = (bits ^ first)
+                    .count_ones()
+                    .min((bits ^ second).count_ones());
+                (distance, data)
+            })
+            .min()?;
+        if distance > 3 {
+            return None;
+        }
+
+        let level = match data >> 3 {
+            1 => QrEcLevel::Low,
+            0 => QrEcLevel::Medium,
+            3 => QrEcLevel::Quartile,
+            _ => QrEcLevel::High,
+        };
+        Some((level, data & 7))
+    }
+
+    /// Check if the module belongs to the function patterns.
+    fn is_function(&self, x: usize, y: usize, version: u32, alignments: &[usize]) -> bool {
+        let size = self.size;
+        let finder = (y < 9 && (x < 9 || x >= size - 8)) || (x < 9 && y >= size - 8);
+        let timing = x == 6 || y == 6;
+        let version_info = version >= 7
+            && ((x >= size - 11 && x < size - 8 && y < 6)
+                || (y >= size - 11 && y < size - 8 && x < 6));
+        let last = alignments.len().saturating_sub(1);
+        let alignment = alignments.iter().enumerate().any(|(i, &ay)| {
+            alignments.iter().enumerate().any(|(j, &ax)| {
+                let corner = (i == 0 && (j == 0 || j == last)) || (i == last && j == 0);
+                !corner && x.abs_diff(ax) <= 2 && y.abs_diff(ay) <= 2
+            })
+        });
+        finder || timing || version_info || alignment
+    }
+
+    /// Decode the symbol of the version.
+    fn decode(&self, version: u32) -> Option<QrCode> {
+        let (ec_level, mask) = self.format()?;
+        let size = self.size;
+        let alignments = alignment_positions(version);
+
+        // read the codewords in the zigzag order from the bottom-right corner
+        let raw_count = raw_data_modules(version) / 8;
+        let mut raw = vec![0u8; raw_count];
+        let mut i = 0;
+        let mut right = size as i64 - 1;
+        while right >= 1 {
+            if right == 6 {
+                right = 5;
+            }
+            for vertical in 0..size {
+                for j in 0..2 {
+                    let x = right as usize - j;
+                    let upward = (right + 1) & 2 == 0;
+                    let y = if upward {
+                        size - 1 - vertical
+                    } else {
+                        vertical
+                    };
+                    if i < raw_count * 8 && !self.is_function(x, y, version, &alignments) {
+                        let bit = self.get(x, y) ^ masked(mask, x, y);
+                        raw[i >> 3] |= (bit as u8) << (7 - (i & 7));
+                        i += 1;
+                    }
+                }
+            }
+            right -= 2;
+        }
+
+        let data = deinterleave(&raw, version, ec_level)?;
+        Some(QrCode {
+            version,
+            ec_level,
+            payload: parse_segments(&data, version)?,
+            corners: self.corners,
+        })
+    }
+}
+
+/// Get the remainder of the BCH code of `data` with the generator of `degree`.
+fn bch(data: u32, generator: u32, degree: u32) -> u32 {
+    let mut remainder = data;
+    for _ in 0..degree {
+        remainder = (remainder << 1) ^ ((remainder >> (degree - 1)) * generator);
+    }
+    remainder & ((1 << degree) - 1)
+}
+
+/// Check if the mask inverts the module.
+fn masked(mask: u32, x: usize, y: usize) -> bool {
+    match mask {
+        0 => (x + y).is_multiple_of(2),
+        1 => y.is_multiple_of(2),
+        2 => x.is_multiple_of(3),
+        3 => (x + y).is_multiple_of(3),
+        4 => (x / 3 + y / 2).is_multiple_of(2),
+        5 => x * y % 2 + x * y % 3 == 0,
+        6 => (x * y % 2 + x * y % 3).is_multiple_of(2),
+        _ => ((x + y) % 2 + x * y % 3).is_multiple_of(2),
+    }
+}
+
+/// Get the centers of the alignment patterns along one axis.
+fn alignment_positions(version: u32) -> Vec<usize> {
+    if version == 1 {
+        return Vec::new();
+    }
+
+    let count = version as usize / 7 + 2;
+    let step = (version as usize * 8 + count * 3 + 5) / (count * 4 - 4) * 2;
+    let size = 17 + 4 * version as usize;
+    let mut positions: Vec<usize> = (0..count - 1).map(|i| size - 7 - i * step).collect();
+    positions.push(6);
+    positions.reverse();
+    positions
+}
+
+/// Get the number of modules of the codewords.
+fn raw_data_modules(version: u32) -> usize {
+    let version = version as usize;
+    let mut result = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let count = version / 7 + 2;
+        result -= (25 * count - 10) * count - 55;
+        if version >= 7 {
+            result -= 36;
+        }
+    }
+    result
+}
+
+/// Error correction codewords per block by the level and the version.
+const EC_CODEWORDS: [[u8; 41]; 4] = [
+    [
+        0, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28,
+        30, 30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28,
+        28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28,
+    ],
+    [
+        0, 13, 22, 18, 26, 18, 24, 18, 22, 20, 24, 28, 26, 24, 20, 30, 24, 28, 28, 26, 30, 28, 30,
+        30, 30, 30, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 17, 28, 22, 16, 22, 28, 26, 26, 24, 28, 24, 28, 22, 24, 24, 30, 28, 28, 26, 28, 30, 24,
+        30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+];
+
+/// Error correction blocks by the level and the version.
+const EC_BLOCKS: [[u8; 41]; 4] = [
+    [
+        0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12, 12, 12, 13,
+        14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25,
+    ],
+    [
+        0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21,
+        23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49,
+    ],
+    [
+        0, 1, 1, 2, 2, 4, 4, 6, 6, 8, 8, 8, 10, 12, 16, 12, 17, 16, 18, 21, 20, 23, 23, 25, 27, 29,
+        34, 34, 35, 38, 40, 43, 45, 48, 51, 53, 56, 59, 62, 65, 68,
+    ],
+    [
+        0, 1, 1, 2, 4, 4, 4, 5, 6, 8, 8, 11, 11, 16, 16, 18, 16, 19, 21, 25, 25, 25, 34, 30, 32,
+        35, 37, 40, 42, 45, 48, 51, 54, 57, 60, 63, 66, 70, 74, 77, 81,
+    ],
+];
+
+/// Split the codewords into the blocks, correct the errors
+/// and join the data codewords.
+fn deinterleave(raw: &[u8], version: u32, level: QrEcLevel) -> Option<Vec<u8>> {
+    let level = level as usize;
+    let ec = EC_CODEWORDS[level][version as usize] as usize;
+    let count = EC_BLOCKS[level][version as usize] as usize;
+    let short_count = count - raw.len() % count;
+    let short_length = raw.len() / count;
+
+    // short blocks have one data codeword less, which is skipped in the order
+    let mut blocks = vec![vec![0u8; short_length + 1]; count];
+    let mut codewords = raw.iter();
+    for i in 0..=short_length {
+        for (j, block) in blocks.iter_mut().enumerate() {
+            if i != short_length - ec || j >= short_count {
+                block[i] = *codewords.next()?;
+            }
+        }
+    }
+
+    let gf = Galois::new();
+    let mut data = Vec::new();
+    for (j, mut block) in blocks.into_iter().enumerate() {
+        if j < short_count {
+            block.remove(short_length - ec);
+        }
+        correct(&gf, &mut block, ec)?;
+        data.extend_from_slice(&block[..block.len() - ec]);
+    }
+    Some(data)
+}
+
+/// Tables of GF(256) with the polynomial `0x11D`.
+struct Galois {
+    exp: [u8; 512],
+    log: [u8; 256],
+}
+
+impl Galois {
+    fn new() -> Self {
+        let (mut exp, mut log) = ([0; 512], [0; 256]);
+        let mut value = 1u32;
+        for (i, power) in exp.iter_mut().take(255).enumerate() {
+            *power = value as u8;
+            log[value as usize] = i as u8;
+            value <<= 1;
+            if value & 0x100 != 0 {
+                value ^= 0x11D;
+            }
+        }
+        exp.copy_within(0..257, 255);
+        Self { exp, log }
+    }
+
+    fn mul(&self, a: u8, b: u8) -> u8 {
+        if a == 0 || b == 0 {
+            0
+        } else {
+            self.exp[self.log[a as usize] as usize + self.log[b as usize] as usize]
+        }
+    }
+
+    fn div(&self, a: u8, b: u8) -> u8 {
+        if a == 0 {
+            0
+        } else {
+            self.exp[self.log[a as usize] as usize + 255 - self.log[b as usize] as usize]
+        }
+    }
+
+    /// Evaluate the polynomial with the lowest coefficient first.
+    fn eval(&self, polynomial: &[u8], x: u8) -> u8 {
+        polynomial
+            .iter()
+            .rev()
+            .fold(0, |value, &coefficient| self.mul(value, x) ^ coefficient)
+    }
+}
+
+/// Correct the Reed-Solomon codeword with `ec` error correction codewords in place.
+fn correct(gf: &Galois, block: &mut [u8], ec: usize) -> Option<()> {
+    let n = block.len();
+    let syndromes = |block: &[u8]| -> Vec<u8> {
+        (0..ec)
+            .map(|j| {
+                block
+                    .iter()
+                    .fold(0, |value, &codeword| gf.mul(value, gf.exp[j]) ^ codeword)
+            })
+            .collect()
+    };
+
+    let syndrome = syndromes(block);
+    if syndrome.iter().all(|&s| s == 0) {
+        return Some(());
+    }
+
+    // Berlekamp-Massey for the error locator with the lowest coefficient first
+    let (mut locator, mut previous) = (vec![1u8], vec![1u8]);
+    let (mut errors, mut shift, mut scale) = (0, 1, 1u8);
+    for i in 0..ec {
+        let discrepancy = (1..=errors).fold(syndrome[i], |d, j| {
+            d ^ gf.mul(*locator.get(j).unwrap_or(&0), syndrome[i - j])
+        });
+        if discrepancy == 0 {
+            shift += 1;
+            continue;
+        }
+
+        let factor = gf.div(discrepancy, scale);
+        let mut next = locator.clone();
+        next.resize(next.len().max(previous.len() + shift), 0);
+        for (j, &coefficient) in previous.iter().enumerate() {
+            next[j + shift] ^= gf.mul(factor, coefficient);
+        }
+
+        if 2 * errors <= i {
+            previous = std::mem::replace(&mut locator, next);
+            errors = i + 1 - errors;
+            scale = discrepancy;
+            shift = 1;
+        } else {
+            locator = next;
+            shift += 1;
+        }
+    }
+    if 2 * errors > ec {
+        return None;
+    }
+
+    // Chien search for the positions, the first codeword has the highest degree
+    let positions: Vec<usize> = (0..n)
+        .filter(|&i| {
+            let inverse = gf.exp[(255 - (n - 1 - i) % 255) % 255];
+            gf.eval(&locator, inverse) == 0
+        })
+        .collect();
+    if positions.len() != errors {
+        return None;
+    }
+
+    // Forney for the magnitudes
+    let mut evaluator: Vec<u8> = vec![0; ec];
+    for (i, &s) in syndrome.iter().enumerate() {
+        for (j, &l) in locator.iter().enumerate() {
+            if i + j < ec {
+                evaluator[i + j] ^= gf.mul(s, l);
+            }
+        }
+    }
+    let derivative: Vec<u8> = locator
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, &l)| if i % 2 == 1 { l } else { 0 })
+        .collect();
+
+    for &position in &positions {
+        let locator_value = gf.exp[(n - 1 - position) % 255];
+        let inverse = gf.div(1, locator_value);
+        let denominator = gf.eval(&derivative, inverse);
+        if denominator == 0 {
+            return None;
+        }
+        let magnitude = gf.mul(
+            locator_value,
+            gf.div(gf.eval(&evaluator, inverse), denominator),
+        );
+        block[position] ^= magnitude;
+    }
+
+    syndromes(block).iter().all(|&s| s == 0).then_some(())
+}
+
+/// Reader of the bits from the highest one.
+struct Bits<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl Bits<'_> {
+    fn remaining(&self) -> usize {
+        self.data.len() * 8 - self.position
+    }
+
+    fn read(&mut self, count: usize) -> Option<u32> {
+        if count > self.remaining() {
+            return None;
+        }
+        let mut value = 0;
+        for _ in 0..count {
+            let bit = self.data[self.position >> 3] >> (7 - (self.position & 7)) & 1;
+            value = value << 1 | bit as u32;
+            self.position += 1;
+        }
+        Some(value)
+    }
+}
+
+const ALPHANUMERIC: &[u8; 45] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+/// Parse the data segments into the payload.
+fn parse_segments(data: &[u8], version: u32) -> Option<Vec<u8>> {
+    let group = match version {
+        1..=9 => 0,
+        10..=26 => 1,
+        _ => 2,
+    };
+    let mut bits = Bits { data, position: 0 };
+    let mut payload = Vec::new();
+
+    while bits.remaining() >= 4 {
+        match bits.read(4)? {
+            0 => break,
+            // numeric
+            1 => {
+                let mut count = bits.read([10, 12, 14][group])?;
+                while count > 0 {
+                    let digits = count.min(3);
+                    let value = bits.read([0, 4, 7, 10][digits as usize])?;
+                    let text = format!("{:0width$}", value, width = digits as usize);
+                    payload.extend_from_slice(text.as_bytes());
+                    count -= digits;
+                }
+            }
+            // alphanumeric
+            2 => {
+                let mut count = bits.read([9, 11, 13][group])?;
+                while count >= 2 {
+                    let value = bits.read(11)? as usize;
+                    payload.push(*ALPHANUMERIC.get(value / 45)?);
+                    payload.push(ALPHANUMERIC[value % 45]);
+                    count -= 2;
+                }
+                if count == 1 {
+                    payload.push(*ALPHANUMERIC.get(bits.read(6)? as usize)?);
+                }
+            }
+            // byte
+            4 => {
+                let count = bits.read([8, 16, 16][group])?;
+                for _ in 0..count {
+                    payload.push(bits.read(8)? as u8);
+                }
+            }
+            // kanji as Shift JIS
+            8 => {
+                let count = bits.read([8, 10, 12][group])?;
+                for _ in 0..count {
+                    let value = bits.read(13)?;
+                    let code = ((value / 0xC0) << 8) | (value % 0xC0);
+                    let code = code + if code < 0x1F00 { 0x8140 } else { 0xC140 };
+                    payload.extend_from_slice(&(code as u16).to_be_bytes());
+                }
+            }
+            // ECI designator
+            7 => {
+                let first = bits.read(8)?;
+                match (first as u8).leading_ones() {
+                    0 => {}
+                    1 => {
+                        bits.read(8)?;
+                    }
+                    _ => {
+                        bits.read(16)?;
+                    }
+                }
+            }
+            // structured append header
+            3 => {
+                bits.read(16)?;
+            }
+            // FNC1 in the first and the second positions
+            5 => {}
+            9 => {
+                bits.read(8)?;
+            }
+            _ => return None,
+        }
+    }
+
+    Some(payload)
+}
@@ -0,0 +1,202 @@
+use crate::BrowserVideoCapture;
+
+/// Metadata of the frame stored in the ring.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct FrameMeta {
+    /// Timestamp in seconds, such as the video `currentTime`.
+    pub timestamp: f64,
+    pub width: u32,
+    pub height: u32,
+    pub channels: u32,
+}
+
+impl FrameMeta {
+    /// Get the size of the frame data in bytes.
+    pub fn buffer_size(&self) -> usize {
+        (self.width * self.height * self.channels) as usize
+    }
+}
+
+/// Frame borrowed from the ring.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameRef<'a> {
+    pub meta: FrameMeta,
+    pub data: &'a [u8],
+}
+
+impl FrameRef<'_> {
+    /// Copy the frame out of the ring.
+    pub fn to_vec(&self) -> (FrameMeta, Vec<u8>) {
+        (self.meta, self.data.to_vec())
+    }
+}
+
+/// Fixed-capacity ring of the latest frames in preallocated slots.
+///
+/// Pushing a frame overwrites the oldest one once the ring is full,
+/// so the ring always holds the history before an event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameRing {
+    slots: Vec<Vec<u8>>,
+    meta: Vec<FrameMeta>,
+    /// Index of the slot written next.
+    head: usize,
+    len: usize,
+    /// Pending incident as the start and the end timestamps.
+    incident: Option<(f64, f64)>,
+}
+
+impl FrameRing {
+    /// Create the ring of `capacity` slots of `slot_size` bytes each.
+    pub fn new(capacity: usize, slot_size: usize) -> Self {
+        Self {
+            slots: vec![vec![0; slot_size]; capacity],
+            meta: vec![FrameMeta::default(); capacity],
+            head: 0,
+            len: 0,
+            incident: None,
+        }
+    }
+
+    /// Create the ring with slots fitting the current capture frame.
+    pub fn for_capture(capture: &(impl BrowserVideoCapture + ?Sized), capacity: usize) -> Self {
+        let (width, height) = capture.capture_size();
+        Self::new(
+            capacity,
+            (width * height * capture.channels_count()) as usize,
+        )
+    }
+
+    /// Get the number of slots.
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Get the number of stored frames.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if the ring has no frames.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the size of one slot in bytes.
+    pub fn slot_size(&self) -> usize {
+        self.slots.first().map_or(0, Vec::len)
+    }
+
+    /// Drop all frames keeping the slots.
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.incident = None;
+    }
+
+    /// Write the next frame into the oldest slot with `fill`.
+    ///
+    /// Returns `false` without writing when the frame does not fit the slot.
+    pub fn push_with(&mut self, meta: FrameMeta, fill: impl FnOnce(&mut [u8])) -> bool {
+        let size = meta.buffer_size();
+        if self.capacity() == 0 || size > self.slot_size() {
+            return false;
+        }
+
+        fill(&mut self.slots[self.head][..size]);
+        self.meta[self.head] = meta;
+        self.head = (self.head + 1) % self.capacity();
+        self.len = (self.len + 1).min(self.capacity());
+        true
+    }
+
+    /// Copy the raw frame into the oldest slot.
+    pub fn push(&mut self, buffer: &[u8], meta: FrameMeta) -> bool {
+        buffer.len() == meta.buffer_size()
+            && self.push_with(meta, |slot| slot.copy_from_slice(buffer))
+    }
+
+    /// Retrieve the captured frame taken at `timestamp` in seconds into the oldest slot.
+    pub fn push_capture(
+        &mut self,
+        capture: &(impl BrowserVideoCapture + ?Sized),
+        timestamp: f64,
+    ) -> bool {
+        let (width, height) = capture.capture_size();
+        let meta = FrameMeta {
+            timestamp,
+            width,
+            height,
+            channels: capture.channels_count(),
+        };
+        self.push_with(meta, |slot| capture.retrieve_rect(0, 0, width, height, slot))
+    }
+
+    /// Get the frame by its age, `0` is the oldest one.
+    pub fn get(&self, index: usize) -> Option<FrameRef<'_>> {
+        if index >= self.len {
+            return None;
+        }
+
+        let slot = (self.head + self.capacity() - self.len + index) % self.capacity();
+        let meta = self.meta[slot];
+        Some(FrameRef {
+            meta,
+            data: &self.slots[slot][..meta.buffer_size()],
+        })
+    }
+
+    /// Get the newest frame.
+    pub fn latest(&self) -> Option<FrameRef<'_>> {
+        self.len.checked_sub(1).and_then(|index| self.get(index))
+    }
+
+    /// Iterate the frames from the oldest to the newest.
+    pub fn iter(&self) -> impl Iterator<Item = FrameRef<'_>> + '_ {
+        (0..self.len).filter_map(move |index| self.get(index))
+    }
+
+    /// Iterate the frames taken in the last `seconds` before the newest one.
+    pub fn last_seconds(&self, seconds: f64) -> impl Iterator<Item = FrameRef<'_>> + '_ {
+        let start = self
+            .latest()
+            .map_or(f64::INFINITY, |frame| frame.meta.timestamp - seconds);
+        self.iter()
+            .filter(move |frame| frame.meta.timestamp >= start)
+    }
+
+    /// Copy the frames taken in the last `seconds` before the newest one.
+    pub fn dump(&self, seconds: f64) -> Vec<(FrameMeta, Vec<u8>)> {
+        self.last_seconds(seconds)
+            .map(|frame| frame.to_vec())
+            .collect()
+    }
+
+    /// Mark the event at `timestamp` to collect `before` seconds of frames
+    /// preceding it and `after` seconds following it with [`FrameRing::poll_incident`].
+    ///
+    /// The ring should have enough slots for the whole incident.
+    pub fn trigger(&mut self, timestamp: f64, before: f64, after: f64) {
+        self.incident = Some((timestamp - before, timestamp + after));
+    }
+
+    /// Check if the incident is waiting for frames after the event.
+    pub fn is_triggered(&self) -> bool {
+        self.incident.is_some()
+    }
+
+    /// Copy the frames of the incident once the frame after its end is pushed.
+    pub fn poll_incident(&mut self) -> Option<Vec<(FrameMeta, Vec<u8>)>> {
+        let (start, end) = self.incident?;
+        if self.latest()?.meta.timestamp < end {
+            return None;
+        }
+
+        self.incident = None;
+        Some(
+            self.iter()
+                .filter(|frame| (start..=end).contains(&frame.meta.timestamp))
+                .map(|frame| frame.to_vec())
+                .collect(),
+        )
+    }
+}
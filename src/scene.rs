@@ -0,0 +1,190 @@
+use std::collections::VecDeque;
+
+use crate::{BrowserVideoCapture, FrameStats};
+
+/// Options of the scene change detector.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SceneOptions {
+    /// Number of the recent histogram distances of the adaptive threshold.
+    pub window: usize,
+    /// Distance above the mean of the recent distances in standard deviations
+    /// of the scene change.
+    pub factor: f64,
+    /// Lower bound of the threshold from `0` to `1`.
+    pub min_threshold: f64,
+    /// Minimal time between the scene changes in seconds.
+    pub min_interval: f64,
+}
+
+impl Default for SceneOptions {
+    fn default() -> Self {
+        Self {
+            window: 30,
+            factor: 3.0,
+            min_threshold: 0.25,
+            min_interval: 0.5,
+        }
+    }
+}
+
+impl SceneOptions {
+    pub fn window(mut self, value: usize) -> Self {
+        self.window = value.max(1);
+        self
+    }
+
+    pub fn factor(mut self, value: f64) -> Self {
+        self.factor = value;
+        self
+    }
+
+    pub fn min_threshold(mut self, value: f64) -> Self {
+        self.min_threshold = value;
+        self
+    }
+
+    pub fn min_interval(mut self, value: f64) -> Self {
+        self.min_interval = value;
+        self
+    }
+}
+
+/// Start of the new scene.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SceneChange {
+    /// Timestamp of the first frame of the scene in seconds.
+    pub timestamp: f64,
+    /// Histogram distance from the previous frame, `1` for the first frame.
+    pub distance: f64,
+    /// Adaptive threshold the distance exceeded.
+    pub threshold: f64,
+}
+
+/// Shot boundary detector comparing the histograms of the consecutive frames
+/// with the threshold adapted to the recent distances.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneDetector {
+    options: SceneOptions,
+    previous: Option<FrameStats>,
+    /// Recent distances within the scenes.
+    distances: VecDeque<f64>,
+    last_change: Option<f64>,
+}
+
+impl SceneDetector {
+    pub fn new(options: SceneOptions) -> Self {
+        Self {
+            options,
+            previous: None,
+            distances: VecDeque::with_capacity(options.window),
+            last_change: None,
+        }
+    }
+
+    pub fn options(&self) -> &SceneOptions {
+        &self.options
+    }
+
+    /// Get the threshold of the next frame.
+    pub fn threshold(&self) -> f64 {
+        let SceneOptions {
+            factor,
+            min_threshold,
+            ..
+        } = self.options;
+        if self.distances.len() < 2 {
+            return min_threshold;
+        }
+
+        let count = self.distances.len() as f64;
+        let mean = self.distances.iter().sum::<f64>() / count;
+        let variance = self
+            .distances
+            .iter()
+            .map(|distance| (distance - mean).powi(2))
+            .sum::<f64>()
+            / count;
+        (mean + factor * variance.sqrt()).max(min_threshold)
+    }
+
+    /// Forget the previous frames, the next frame starts the new scene.
+    pub fn reset(&mut self) {
+        self.previous = None;
+        self.distances.clear();
+        self.last_change = None;
+    }
+
+    /// Compare the histograms of the frame taken at `timestamp` in seconds
+    /// with the previous frame, the first frame starts the first scene.
+    pub fn update(&mut self, stats: FrameStats, timestamp: f64) -> Option<SceneChange> {
+        let threshold = self.threshold();
+        let distance = self
+            .previous
+            .as_ref()
+            .map(|previous| previous.distance(&stats));
+        self.previous = Some(stats);
+
+        let Some(distance) = distance else {
+            self.last_change = Some(timestamp);
+            return Some(SceneChange {
+                timestamp,
+                distance: 1.0,
+                threshold,
+            });
+        };
+        let settled = self
+            .last_change
+            .is_none_or(|last| (timestamp - last).abs() >= self.options.min_interval);
+
+        if distance > threshold {
+            if !settled {
+                return None;
+            }
+            self.last_change = Some(timestamp);
+            return Some(SceneChange {
+                timestamp,
+                distance,
+                threshold,
+            });
+        }
+
+        // scene changes stay out of the window, so they do not raise the threshold
+        while self.distances.len() >= self.options.window.max(1) {
+            self.distances.pop_front();
+        }
+        self.distances.push_back(distance);
+        None
+    }
+
+    /// Compare the captured frame taken at `timestamp` in seconds with the previous one.
+    pub fn detect(
+        &mut self,
+        capture: &(impl BrowserVideoCapture + ?Sized),
+        timestamp: f64,
+    ) -> Option<SceneChange> {
+        self.update(capture.stats(), timestamp)
+    }
+
+    /// Seek the video by `step` seconds from `start` to its end, capture every frame
+    /// with `mode` and get the first frames of the scenes as tightly packed RGBA.
+    #[cfg(feature = "seek")]
+    pub async fn scan(
+        &mut self,
+        capture: &(impl BrowserVideoCapture + ?Sized),
+        video: &web_sys::HtmlVideoElement,
+        mode: crate::CaptureMode,
+        start: f64,
+        step: f64,
+    ) -> Result<Vec<(SceneChange, Vec<u8>)>, web_sys::js_sys::Error> {
+        let mut scenes = Vec::new();
+        for timestamp in crate::seek_interval(start, step, video.duration()) {
+            crate::seek(video, timestamp).await?;
+            capture.capture(video, mode);
+            if let Some(change) = self.detect(capture, timestamp) {
+                scenes.push((change, capture.packed_data()));
+            }
+        }
+
+        Ok(scenes)
+    }
+}
@@ -0,0 +1,112 @@
+/// Get the timestamps from `start` by `step` seconds before `end`,
+/// such as the video duration.
+pub fn seek_interval(start: f64, step: f64, end: f64) -> Vec<f64> {
+    if step <= 0.0 || step.is_nan() || !end.is_finite() {
+        return Vec::new();
+    }
+
+    (0..)
+        .map(|index| start + index as f64 * step)
+        .take_while(|timestamp| *timestamp < end)
+        .collect()
+}
+
+#[cfg(feature = "seek")]
+pub use video::*;
+
+#[cfg(feature = "seek")]
+mod video {
+    use wasm_bindgen_futures::JsFuture;
+    use web_sys::{js_sys, wasm_bindgen::JsCast, HtmlMediaElement, HtmlVideoElement};
+
+    use crate::{BrowserVideoCapture, CaptureMode, FrameMeta};
+
+    /// Event the seek waits for before the frame is captured.
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum SeekWait {
+        /// The `seeked` event of the video.
+        #[default]
+        Seeked,
+        /// The `requestVideoFrameCallback` callback of the seeked frame,
+        /// which is presented for sure, or the `seeked` event without the callback.
+        ///
+        /// The callback never fires when the seek presents no new frame,
+        /// such as the timestamp inside the shown frame.
+        VideoFrame,
+    }
+
+    /// Seek the video to `time` in seconds and wait for the `seeked` event,
+    /// so the next capture takes the frame at `time`.
+    pub async fn seek(video: &HtmlVideoElement, time: f64) -> Result<(), js_sys::Error> {
+        seek_with(video, time, SeekWait::Seeked).await
+    }
+
+    /// Seek the video to `time` in seconds and wait for the `wait` event,
+    /// the video without loaded metadata fails.
+    pub async fn seek_with(
+        video: &HtmlVideoElement,
+        time: f64,
+        wait: SeekWait,
+    ) -> Result<(), js_sys::Error> {
+        // the video without metadata never fires `seeked`
+        if video.ready_state() == HtmlMediaElement::HAVE_NOTHING {
+            return Err(js_sys::Error::new("the video has no metadata to seek"));
+        }
+
+        // the callback is requested before seeking so the seeked frame is not missed
+        let callback = js_sys::Reflect::get(video, &"requestVideoFrameCallback".into())
+            .ok()
+            .and_then(|callback| callback.dyn_into::<js_sys::Function>().ok())
+            .filter(|_| wait == SeekWait::VideoFrame);
+        let presented = callback.map(|callback| {
+            js_sys::Promise::new(&mut |resolve, _| {
+                let _ = callback.call1(video, &resolve);
+            })
+        });
+
+        // the listeners leave the handlers of the page untouched
+        let mut listeners = None;
+        let seeked = js_sys::Promise::new(&mut |resolve, reject| {
+            let _ = video.add_event_listener_with_callback("seeked", &resolve);
+            let _ = video.add_event_listener_with_callback("error", &reject);
+            listeners = Some((resolve, reject));
+        });
+        video.set_current_time(time);
+        let result = JsFuture::from(seeked).await;
+        if let Some((resolve, reject)) = listeners {
+            let _ = video.remove_event_listener_with_callback("seeked", &resolve);
+            let _ = video.remove_event_listener_with_callback("error", &reject);
+        }
+        result.map_err(|_| js_sys::Error::new(&format!("failed to seek the video to {}", time)))?;
+
+        if let Some(presented) = presented {
+            JsFuture::from(presented).await?;
+        }
+        Ok(())
+    }
+
+    /// Seek the video to every timestamp, capture the frame with `mode`
+    /// and get the frames as tightly packed data.
+    pub async fn extract_frames(
+        capture: &(impl BrowserVideoCapture + ?Sized),
+        video: &HtmlVideoElement,
+        timestamps: &[f64],
+        mode: CaptureMode,
+        wait: SeekWait,
+    ) -> Result<Vec<(FrameMeta, Vec<u8>)>, js_sys::Error> {
+        let mut frames = Vec::with_capacity(timestamps.len());
+        for &timestamp in timestamps {
+            seek_with(video, timestamp, wait).await?;
+            capture.capture(video, mode);
+            let (width, height) = capture.capture_size();
+            let meta = FrameMeta {
+                timestamp,
+                width,
+                height,
+                channels: capture.channels_count(),
+            };
+            frames.push((meta, capture.packed_data()));
+        }
+        Ok(frames)
+    }
+}
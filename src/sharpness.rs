@@ -0,0 +1,153 @@
+use std::collections::VecDeque;
+
+use crate::{stats::luma, FrameRef, FrameRing, Rect};
+
+/// Focus measure of the frame, higher for sharper frames.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SharpnessMethod {
+    /// Variance of the 4-neighbor Laplacian of the luminance.
+    #[default]
+    Laplacian,
+    /// Mean squared magnitude of the Sobel gradient of the luminance.
+    Tenengrad,
+}
+
+impl SharpnessMethod {
+    /// Score the raw frame with `channels` bytes per pixel inside `rect`,
+    /// or the whole frame for `None`, `0` for the empty area.
+    ///
+    /// Neighbors out of the frame repeat the edge pixels.
+    pub fn score(
+        &self,
+        buffer: &[u8],
+        (width, height): (u32, u32),
+        channels: u32,
+        rect: Option<Rect>,
+    ) -> f64 {
+        let frame = Rect::new(0, 0, width, height);
+        let rect = match rect {
+            Some(rect) => frame.clip(rect.x as i32, rect.y as i32, rect.width, rect.height),
+            None => frame,
+        };
+        if rect.area() == 0 || buffer.len() < (width * height * channels) as usize {
+            return 0.0;
+        }
+
+        let value = |x: i64, y: i64| {
+            let x = x.clamp(0, width as i64 - 1) as usize;
+            let y = y.clamp(0, height as i64 - 1) as usize;
+            let index = (y * width as usize + x) * channels as usize;
+            let value = match buffer[index..index + channels as usize] {
+                [l] | [l, _] => l,
+                [r, g, b, ..] => luma(r, g, b),
+                [] => unreachable!(),
+            };
+            value as f64
+        };
+
+        let (mut sum, mut squares) = (0.0, 0.0);
+        for y in rect.y as i64..(rect.y + rect.height) as i64 {
+            for x in rect.x as i64..(rect.x + rect.width) as i64 {
+                match self {
+                    SharpnessMethod::Laplacian => {
+                        let laplacian =
+                            value(x - 1, y) + value(x + 1, y) + value(x, y - 1) + value(x, y + 1)
+                                - 4.0 * value(x, y);
+                        sum += laplacian;
+                        squares += laplacian * laplacian;
+                    }
+                    SharpnessMethod::Tenengrad => {
+                        let gx = value(x + 1, y - 1) + 2.0 * value(x + 1, y) + value(x + 1, y + 1)
+                            - value(x - 1, y - 1)
+                            - 2.0 * value(x - 1, y)
+                            - value(x - 1, y + 1);
+                        let gy = value(x - 1, y + 1) + 2.0 * value(x, y + 1) + value(x + 1, y + 1)
+                            - value(x - 1, y - 1)
+                            - 2.0 * value(x, y - 1)
+                            - value(x + 1, y - 1);
+                        squares += gx * gx + gy * gy;
+                    }
+                }
+            }
+        }
+
+        self.finish(sum, squares, rect.area())
+    }
+
+    /// Get the score from the sums of the per-pixel measure and its square
+    /// over `count` pixels.
+    pub(crate) fn finish(&self, sum: f64, squares: f64, count: u32) -> f64 {
+        if count == 0 {
+            return 0.0;
+        }
+
+        let count = count as f64;
+        match self {
+            SharpnessMethod::Laplacian => (squares / count - (sum / count).powi(2)).max(0.0),
+            SharpnessMethod::Tenengrad => squares / count,
+        }
+    }
+}
+
+/// Selector of the sharpest frame among the last `window` scored frames,
+/// such as the frames scored on the GPU while they are pushed into the [`FrameRing`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharpestFrame {
+    window: usize,
+    /// Timestamps and scores from the oldest frame.
+    scores: VecDeque<(f64, f64)>,
+}
+
+impl SharpestFrame {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(1),
+            scores: VecDeque::with_capacity(window.max(1)),
+        }
+    }
+
+    /// Add the score of the frame taken at `timestamp`, dropping the oldest one
+    /// out of the window, and check if it is the sharpest frame now.
+    pub fn push(&mut self, timestamp: f64, score: f64) -> bool {
+        if self.scores.len() == self.window {
+            self.scores.pop_front();
+        }
+        self.scores.push_back((timestamp, score));
+        self.best() == Some((timestamp, score))
+    }
+
+    /// Get the timestamp and the score of the sharpest frame in the window,
+    /// the newest one of equal scores.
+    pub fn best(&self) -> Option<(f64, f64)> {
+        self.scores
+            .iter()
+            .copied()
+            .reduce(|best, frame| if frame.1 >= best.1 { frame } else { best })
+    }
+
+    /// Find the sharpest frame in the ring by its timestamp.
+    pub fn find<'a>(&self, ring: &'a FrameRing) -> Option<FrameRef<'a>> {
+        let (timestamp, _) = self.best()?;
+        ring.iter().find(|frame| frame.meta.timestamp == timestamp)
+    }
+
+    pub fn clear(&mut self) {
+        self.scores.clear();
+    }
+}
+
+impl FrameRing {
+    /// Get the sharpest of the last `count` frames scored on the CPU.
+    pub fn sharpest(&self, count: usize, method: SharpnessMethod) -> Option<FrameRef<'_>> {
+        let skip = self.len().saturating_sub(count);
+        self.iter()
+            .skip(skip)
+            .map(|frame| {
+                let meta = frame.meta;
+                let size = (meta.width, meta.height);
+                (method.score(frame.data, size, meta.channels, None), frame)
+            })
+            .reduce(|best, frame| if frame.0 >= best.0 { frame } else { best })
+            .map(|(_, frame)| frame)
+    }
+}
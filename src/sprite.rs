@@ -0,0 +1,187 @@
+use crate::{utils::expand_rgba, FrameMeta, Mosaic, Rect, Resample};
+
+/// Thumbnail of the sprite sheet shown from `start` to `end` in seconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpriteCue {
+    pub start: f64,
+    pub end: f64,
+    pub tile: Rect,
+}
+
+/// Grid of RGBA thumbnails in one image with the WebVTT index,
+/// such as the scrubbing preview of the video.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpriteSheet {
+    mosaic: Mosaic,
+    size: (u32, u32),
+    data: Vec<u8>,
+    timestamps: Vec<f64>,
+}
+
+impl SpriteSheet {
+    /// Create the sheet of `count` tiles of `tile_size` in `columns` columns.
+    pub fn new(count: usize, columns: u32, tile_size: (u32, u32)) -> Self {
+        let columns = columns.clamp(1, count.max(1) as u32);
+        let rows = (count as u32).div_ceil(columns);
+        let mosaic = Mosaic::grid(columns, rows, tile_size.0, tile_size.1);
+        let (width, height) = mosaic.size();
+
+        Self {
+            size: (width, height),
+            data: vec![0; (width * height * 4) as usize],
+            mosaic: Mosaic::new(mosaic.tiles()[..count].to_vec()),
+            timestamps: Vec::with_capacity(count),
+        }
+    }
+
+    /// Create the sheet from the frames, such as the frames extracted by
+    /// seeking the video.
+    pub fn from_frames(
+        frames: &[(FrameMeta, Vec<u8>)],
+        columns: u32,
+        tile_size: (u32, u32),
+    ) -> Self {
+        let mut sheet = Self::new(frames.len(), columns, tile_size);
+        for (meta, data) in frames {
+            sheet.push(meta, data);
+        }
+        sheet
+    }
+
+    /// Get the size of the sheet image in pixels.
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    /// Get the tiles of the sheet.
+    pub fn mosaic(&self) -> &Mosaic {
+        &self.mosaic
+    }
+
+    /// Get the RGBA data of the sheet image.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Get the number of added thumbnails.
+    pub fn len(&self) -> usize {
+        self.timestamps.len()
+    }
+
+    /// Check if the sheet has no thumbnails.
+    pub fn is_empty(&self) -> bool {
+        self.timestamps.is_empty()
+    }
+
+    /// Check if every tile holds a thumbnail.
+    pub fn is_full(&self) -> bool {
+        self.timestamps.len() == self.mosaic.len()
+    }
+
+    /// Downscale the frame into the next tile.
+    ///
+    /// Returns `false` when the sheet is full or the data is shorter than the frame.
+    pub fn push(&mut self, meta: &FrameMeta, data: &[u8]) -> bool {
+        let Some(tile) = self.mosaic.tile(self.timestamps.len()) else {
+            return false;
+        };
+        let Some(length) = (meta.width as usize)
+            .checked_mul(meta.height as usize)
+            .and_then(|area| area.checked_mul(meta.channels as usize))
+            .filter(|&length| length <= data.len() && (1..=4).contains(&meta.channels))
+        else {
+            return false;
+        };
+        let frame = expand_rgba(&data[..length], meta.channels);
+        let mut thumbnail = vec![0; tile.area() as usize * 4];
+
+        if (meta.width, meta.height) == tile.size() {
+            thumbnail.copy_from_slice(&frame);
+        } else {
+            crate::utils::remap_rgba(
+                &frame,
+                (meta.width, meta.height),
+                &mut thumbnail,
+                tile.size(),
+                Resample::Area,
+                |x, y| Some((x, y)),
+            );
+        }
+
+        let stride = self.size().0 as usize * 4;
+        let row = tile.width as usize * 4;
+        for (y, line) in thumbnail.chunks_exact(row).enumerate() {
+            let start = tile.offset(stride, 4) + y * stride;
+            self.data[start..start + row].copy_from_slice(line);
+        }
+        self.timestamps.push(meta.timestamp);
+        true
+    }
+
+    /// Get the cues of the thumbnails, each one lasts until the next thumbnail
+    /// and the last one until `duration` in seconds.
+    pub fn cues(&self, duration: f64) -> Vec<SpriteCue> {
+        self.timestamps
+            .iter()
+            .enumerate()
+            .map(|(index, &start)| SpriteCue {
+                start,
+                end: self.timestamps.get(index + 1).copied().unwrap_or(duration),
+                tile: self.mosaic.tiles()[index],
+            })
+            .collect()
+    }
+
+    /// Get the WebVTT index of the thumbnails in the sheet image at `url`
+    /// with the media fragments of the tiles.
+    pub fn vtt(&self, url: &str, duration: f64) -> String {
+        let mut vtt = String::from("WEBVTT\n");
+        for cue in self.cues(duration) {
+            let Rect {
+                x,
+                y,
+                width,
+                height,
+            } = cue.tile;
+            vtt += &format!(
+                "\n{} --> {}\n{}#xywh={},{},{},{}\n",
+                vtt_time(cue.start),
+                vtt_time(cue.end),
+                url,
+                x,
+                y,
+                width,
+                height
+            );
+        }
+        vtt
+    }
+
+    /// Seek the video to every timestamp waiting for the `wait` event,
+    /// capture the frame with `mode` and place the frames into the sheet.
+    #[cfg(feature = "seek")]
+    pub async fn extract(
+        capture: &(impl crate::BrowserVideoCapture + ?Sized),
+        video: &web_sys::HtmlVideoElement,
+        timestamps: &[f64],
+        mode: crate::CaptureMode,
+        wait: crate::SeekWait,
+        columns: u32,
+        tile_size: (u32, u32),
+    ) -> Result<Self, web_sys::js_sys::Error> {
+        let frames = crate::extract_frames(capture, video, timestamps, mode, wait).await?;
+        Ok(Self::from_frames(&frames, columns, tile_size))
+    }
+}
+
+/// Format the time in seconds as `hh:mm:ss.ttt`.
+fn vtt_time(seconds: f64) -> String {
+    let millis = (seconds.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
@@ -0,0 +1,135 @@
+/// Channel of the frame statistics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatsChannel {
+    Red,
+    Green,
+    Blue,
+    Alpha,
+    /// BT.601 luminance of the full range RGB.
+    Luma,
+}
+
+impl StatsChannel {
+    /// All channels in the order of [`FrameStats::histograms`].
+    pub const ALL: [StatsChannel; 5] = [
+        StatsChannel::Red,
+        StatsChannel::Green,
+        StatsChannel::Blue,
+        StatsChannel::Alpha,
+        StatsChannel::Luma,
+    ];
+}
+
+/// Get the BT.601 luminance of the pixel.
+pub(crate) fn luma(r: u8, g: u8, b: u8) -> u8 {
+    ((299 * r as u32 + 587 * g as u32 + 114 * b as u32 + 500) / 1000) as u8
+}
+
+/// Histograms of the captured frame with the statistics derived from them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameStats {
+    /// Histograms of the red, green, blue, alpha channels and the luminance.
+    pub histograms: [[u32; 256]; 5],
+}
+
+impl FrameStats {
+    /// Compute the histograms of the raw frame with `channels` bytes per pixel,
+    /// frames without colors have equal red, green and blue channels.
+    pub fn from_frame(buffer: &[u8], channels: u32) -> Self {
+        let mut histograms = [[0; 256]; 5];
+
+        for pixel in buffer.chunks_exact(channels as usize) {
+            let (r, g, b, a) = match *pixel {
+                [l] => (l, l, l, 255),
+                [l, a] => (l, l, l, a),
+                [r, g, b] => (r, g, b, 255),
+                [r, g, b, a, ..] => (r, g, b, a),
+                [] => unreachable!(),
+            };
+
+            histograms[0][r as usize] += 1;
+            histograms[1][g as usize] += 1;
+            histograms[2][b as usize] += 1;
+            histograms[3][a as usize] += 1;
+            histograms[4][luma(r, g, b) as usize] += 1;
+        }
+
+        Self { histograms }
+    }
+
+    /// Get the histogram of the channel.
+    pub fn histogram(&self, channel: StatsChannel) -> &[u32; 256] {
+        &self.histograms[channel as usize]
+    }
+
+    /// Get the number of pixels.
+    pub fn count(&self) -> u64 {
+        self.histograms[0].iter().map(|&count| count as u64).sum()
+    }
+
+    /// Get the mean value of the channel, `0` for the empty frame.
+    pub fn mean(&self, channel: StatsChannel) -> f64 {
+        let (sum, count) = self
+            .histogram(channel)
+            .iter()
+            .enumerate()
+            .fold((0, 0), |(sum, count), (value, &n)| {
+                (sum + value as u64 * n as u64, count + n as u64)
+            });
+
+        if count > 0 {
+            sum as f64 / count as f64
+        } else {
+            0.0
+        }
+    }
+
+    /// Get the minimum value of the channel, `None` for the empty frame.
+    pub fn min(&self, channel: StatsChannel) -> Option<u8> {
+        self.histogram(channel)
+            .iter()
+            .position(|&n| n > 0)
+            .map(|value| value as u8)
+    }
+
+    /// Get the maximum value of the channel, `None` for the empty frame.
+    pub fn max(&self, channel: StatsChannel) -> Option<u8> {
+        self.histogram(channel)
+            .iter()
+            .rposition(|&n| n > 0)
+            .map(|value| value as u8)
+    }
+
+    /// Get the distance of the color distributions from `0` for equal histograms
+    /// to `1` for disjoint ones, as the mean total variation distance
+    /// of the red, green and blue histograms.
+    pub fn distance(&self, other: &FrameStats) -> f64 {
+        let (count, other_count) = (self.count(), other.count());
+        if count == 0 || other_count == 0 {
+            return if count == other_count { 0.0 } else { 1.0 };
+        }
+
+        let total: f64 = self.histograms[..3]
+            .iter()
+            .zip(&other.histograms[..3])
+            .flat_map(|(a, b)| a.iter().zip(b))
+            .map(|(&a, &b)| (a as f64 / count as f64 - b as f64 / other_count as f64).abs())
+            .sum();
+        total / 6.0
+    }
+
+    /// Get the fraction of pixels with the luminance below `threshold`,
+    /// such as for "too dark" warnings.
+    pub fn dark_fraction(&self, threshold: u8) -> f64 {
+        let histogram = self.histogram(StatsChannel::Luma);
+        let dark: u64 = histogram[..threshold as usize]
+            .iter()
+            .map(|&n| n as u64)
+            .sum();
+
+        match self.count() {
+            0 => 0.0,
+            count => dark as f64 / count as f64,
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use web_sys::{
+    js_sys,
+    wasm_bindgen::{JsCast, JsValue},
+    CanvasCaptureMediaStreamTrack, HtmlCanvasElement, MediaStream,
+};
+
+/// How the canvas stream produces frames.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum StreamMode {
+    /// New frame every time the canvas changes.
+    #[default]
+    Auto,
+    /// New frame at most `fps` times per second when the canvas changes.
+    Rate(f64),
+    /// New frame only on [`CanvasStream::request_frame`],
+    /// so every captured frame can be forwarded exactly once.
+    Manual,
+}
+
+/// Media stream of the capture canvas content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CanvasStream {
+    stream: MediaStream,
+    track: CanvasCaptureMediaStreamTrack,
+}
+
+impl CanvasStream {
+    /// Start capturing the canvas content into the new stream.
+    pub fn new(canvas: &HtmlCanvasElement, mode: StreamMode) -> Result<Self, js_sys::Error> {
+        let stream = match mode {
+            StreamMode::Auto => canvas.capture_stream(),
+            StreamMode::Rate(fps) => canvas.capture_stream_with_frame_request_rate(fps),
+            StreamMode::Manual => canvas.capture_stream_with_frame_request_rate(0.0),
+        }?;
+
+        let track = stream
+            .get_video_tracks()
+            .get(0)
+            .dyn_into::<CanvasCaptureMediaStreamTrack>()
+            .map_err(|_| js_sys::Error::new("Canvas stream has no video track"))?;
+
+        Ok(Self { stream, track })
+    }
+
+    /// Get the media stream, for example to show it in a video element
+    /// or to send it over a peer connection.
+    pub fn stream(&self) -> &MediaStream {
+        &self.stream
+    }
+
+    /// Get the video track of the stream.
+    pub fn track(&self) -> &CanvasCaptureMediaStreamTrack {
+        &self.track
+    }
+
+    /// Push the current canvas content as the next frame of the stream.
+    pub fn request_frame(&self) {
+        self.track.request_frame();
+    }
+
+    /// Stop the stream.
+    pub fn stop(&self) {
+        self.track.stop();
+    }
+}
+
+impl From<CanvasStream> for MediaStream {
+    fn from(value: CanvasStream) -> Self {
+        value.stream
+    }
+}
+
+impl From<CanvasStream> for JsValue {
+    fn from(value: CanvasStream) -> Self {
+        value.stream.into()
+    }
+}
+
+#[cfg(feature = "recorder")]
+pub use recorder::StreamRecorder;
+
+#[cfg(feature = "recorder")]
+mod recorder {
+    use std::{cell::RefCell, rc::Rc};
+
+    use wasm_bindgen_futures::JsFuture;
+    use web_sys::{
+        js_sys,
+        wasm_bindgen::{closure::Closure, JsCast},
+        Blob, BlobEvent, BlobPropertyBag, MediaRecorder, MediaRecorderOptions, MediaStream,
+    };
+
+    /// Recorder of the media stream into the video blob with `MediaRecorder`.
+    #[derive(Debug)]
+    pub struct StreamRecorder {
+        recorder: MediaRecorder,
+        chunks: Rc<RefCell<Vec<Blob>>>,
+        _on_data: Closure<dyn FnMut(BlobEvent)>,
+    }
+
+    impl StreamRecorder {
+        /// Default container of the recording.
+        pub const WEBM: &'static str = "video/webm";
+
+        /// Create the recorder of the stream into `mime_type`, such as
+        /// [`StreamRecorder::WEBM`] or `"video/webm;codecs=vp9"`,
+        /// with the optional video bitrate.
+        pub fn new(
+            stream: &MediaStream,
+            mime_type: &str,
+            bits_per_second: Option<u32>,
+        ) -> Result<Self, js_sys::Error> {
+            let options = MediaRecorderOptions::new();
+            options.set_mime_type(mime_type);
+            if let Some(bits) = bits_per_second {
+                options.set_video_bits_per_second(bits);
+            }
+
+            let recorder =
+                MediaRecorder::new_with_media_stream_and_media_recorder_options(stream, &options)?;
+
+            let chunks = Rc::new(RefCell::new(Vec::new()));
+            let on_data = {
+                let chunks = chunks.clone();
+                Closure::<dyn FnMut(BlobEvent)>::new(move |event: BlobEvent| {
+                    if let Some(blob) = event.data().filter(|blob| blob.size() > 0.0) {
+                        chunks.borrow_mut().push(blob);
+                    }
+                })
+            };
+            recorder.set_ondataavailable(Some(on_data.as_ref().unchecked_ref()));
+
+            Ok(Self {
+                recorder,
+                chunks,
+                _on_data: on_data,
+            })
+        }
+
+        /// Check if the browser can record into `mime_type`.
+        pub fn is_type_supported(mime_type: &str) -> bool {
+            MediaRecorder::is_type_supported(mime_type)
+        }
+
+        /// Get the underlying recorder.
+        pub fn recorder(&self) -> &MediaRecorder {
+            &self.recorder
+        }
+
+        /// Start recording, the data is flushed every `timeslice` milliseconds when set.
+        pub fn start(&self, timeslice: Option<i32>) -> Result<(), js_sys::Error> {
+            self.chunks.borrow_mut().clear();
+            match timeslice {
+                Some(timeslice) => self.recorder.start_with_time_slice(timeslice),
+                None => self.recorder.start(),
+            }
+            .map_err(Into::into)
+        }
+
+        /// Stop recording and get the recorded video.
+        pub async fn stop(&self) -> Result<Blob, js_sys::Error> {
+            let mut result = Ok(());
+            let stopped = js_sys::Promise::new(&mut |resolve, _| {
+                self.recorder.set_onstop(Some(&resolve));
+                result = self.recorder.stop();
+            });
+            result?;
+            JsFuture::from(stopped).await?;
+            self.recorder.set_onstop(None);
+
+            let chunks: js_sys::Array = self.chunks.borrow_mut().drain(..).collect();
+            let options = BlobPropertyBag::new();
+            options.set_type(&self.recorder.mime_type());
+
+            Ok(Blob::new_with_blob_sequence_and_options(&chunks, &options)?)
+        }
+    }
+
+    impl Drop for StreamRecorder {
+        fn drop(&mut self) {
+            self.recorder.set_ondataavailable(None);
+        }
+    }
+}
@@ -0,0 +1,118 @@
+/// Mode of the temporal filter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TemporalMode {
+    /// Exponential average of the frames, where `strength` from `0` to `1`
+    /// is the weight of the history on still pixels.
+    ///
+    /// Pixels which luminance changes by more than `motion_threshold` from `0` to `255`
+    /// take the new frame to avoid ghosting, `0` disables the adaptation.
+    Denoise {
+        strength: f32,
+        motion_threshold: f32,
+    },
+    /// Average of the frames for the long exposure, the first `n` frames are
+    /// stacked with equal weights, then every frame has the weight `1 / n`.
+    Stack(u32),
+}
+
+impl Default for TemporalMode {
+    fn default() -> Self {
+        TemporalMode::Denoise {
+            strength: 0.8,
+            motion_threshold: 32.0,
+        }
+    }
+}
+
+impl TemporalMode {
+    /// Get the weight of the new frame on still pixels and the motion threshold
+    /// from `0` to `1` for the frame number `frames` of the history.
+    pub(crate) fn weights(&self, frames: u32) -> (f32, f32) {
+        match *self {
+            _ if frames <= 1 => (1.0, 0.0),
+            TemporalMode::Denoise {
+                strength,
+                motion_threshold,
+            } => (
+                1.0 - strength.clamp(0.0, 1.0),
+                motion_threshold.max(0.0) / 255.0,
+            ),
+            TemporalMode::Stack(n) => (1.0 / frames.min(n.max(1)) as f32, 0.0),
+        }
+    }
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Temporal filter of the raw RGBA frames on the CPU with the float history,
+/// the WebGL captures run the same filter on the GPU.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemporalFilter {
+    mode: TemporalMode,
+    size: (u32, u32),
+    frames: u32,
+    history: Vec<f32>,
+}
+
+impl TemporalFilter {
+    pub fn new(mode: TemporalMode) -> Self {
+        Self {
+            mode,
+            size: (0, 0),
+            frames: 0,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn mode(&self) -> TemporalMode {
+        self.mode
+    }
+
+    /// Get the number of frames in the history.
+    pub fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    /// Forget the history.
+    pub fn reset(&mut self) {
+        self.size = (0, 0);
+        self.frames = 0;
+        self.history.clear();
+    }
+
+    /// Blend the RGBA frame with the history and replace it with the result,
+    /// the frame of the new size restarts the history.
+    pub fn apply(&mut self, buffer: &mut [u8], size: (u32, u32)) {
+        if self.size != size {
+            self.reset();
+            self.size = size;
+        }
+        self.frames = self.frames.saturating_add(1);
+        self.history.resize(buffer.len(), 0.0);
+
+        let (weight, threshold) = self.mode.weights(self.frames);
+        for (pixel, history) in buffer
+            .chunks_exact_mut(4)
+            .zip(self.history.chunks_exact_mut(4))
+        {
+            let weight = if threshold > 0.0 {
+                let diff = (0.299 * (pixel[0] as f32 - history[0])
+                    + 0.587 * (pixel[1] as f32 - history[1])
+                    + 0.114 * (pixel[2] as f32 - history[2]))
+                    .abs()
+                    / 255.0;
+                weight + (1.0 - weight) * smoothstep(0.5 * threshold, threshold, diff)
+            } else {
+                weight
+            };
+
+            for (value, history) in pixel.iter_mut().zip(history.iter_mut()) {
+                *history += weight * (*value as f32 - *history);
+                *value = history.round() as u8;
+            }
+        }
+    }
+}
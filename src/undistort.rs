@@ -0,0 +1,148 @@
+use crate::{utils::Filter, Resample};
+
+/// Lens distortion correction from OpenCV-style camera intrinsics.
+///
+/// The camera matrix `(fx, fy, cx, cy)` is in pixels of the video frame,
+/// the distortion coefficients follow OpenCV order `(k1, k2, p1, p2, k3)`.
+/// The corrected frame keeps the same camera matrix and size.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct Undistort {
+    pub fx: f64,
+    pub fy: f64,
+    pub cx: f64,
+    pub cy: f64,
+    pub k1: f64,
+    pub k2: f64,
+    pub p1: f64,
+    pub p2: f64,
+    pub k3: f64,
+}
+
+impl Undistort {
+    /// Create the correction for the camera matrix without any distortion.
+    pub fn new(fx: f64, fy: f64, cx: f64, cy: f64) -> Self {
+        Self {
+            fx,
+            fy,
+            cx,
+            cy,
+            ..Default::default()
+        }
+    }
+
+    /// Set the radial distortion coefficients.
+    pub fn radial(mut self, k1: f64, k2: f64, k3: f64) -> Self {
+        self.k1 = k1;
+        self.k2 = k2;
+        self.k3 = k3;
+        self
+    }
+
+    /// Set the tangential distortion coefficients.
+    pub fn tangential(mut self, p1: f64, p2: f64) -> Self {
+        self.p1 = p1;
+        self.p2 = p2;
+        self
+    }
+
+    /// Map the pixel of the corrected frame to the pixel of the distorted video frame.
+    pub fn distort(&self, x: f64, y: f64) -> (f64, f64) {
+        let (x, y) = ((x - self.cx) / self.fx, (y - self.cy) / self.fy);
+
+        let r2 = x * x + y * y;
+        let radial = 1.0 + r2 * (self.k1 + r2 * (self.k2 + r2 * self.k3));
+        let dx = x * radial + 2.0 * self.p1 * x * y + self.p2 * (r2 + 2.0 * x * x);
+        let dy = y * radial + self.p1 * (r2 + 2.0 * y * y) + 2.0 * self.p2 * x * y;
+
+        (dx * self.fx + self.cx, dy * self.fy + self.cy)
+    }
+
+    /// Get the camera matrix as `(fx, fy, cx, cy)` for the shader uniform.
+    #[cfg(feature = "gl")]
+    pub(crate) fn camera_gl(&self) -> [f32; 4] {
+        [self.fx, self.fy, self.cx, self.cy].map(|value| value as f32)
+    }
+
+    /// Get the radial coefficients as `(k1, k2, k3)` for the shader uniform.
+    #[cfg(feature = "gl")]
+    pub(crate) fn radial_gl(&self) -> [f32; 3] {
+        [self.k1, self.k2, self.k3].map(|value| value as f32)
+    }
+
+    /// Get the tangential coefficients as `(p1, p2)` for the shader uniform.
+    #[cfg(feature = "gl")]
+    pub(crate) fn tangential_gl(&self) -> [f32; 2] {
+        [self.p1, self.p2].map(|value| value as f32)
+    }
+}
+
+/// Precomputed source positions of the lens correction for a fixed frame size.
+///
+/// Positions are stored in fixed point with 8 fractional bits,
+/// applying the table samples them with the filter given to [`apply`](Self::apply).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemapTable {
+    source: (u32, u32),
+    target: (u32, u32),
+    positions: Vec<Option<(u32, u32)>>,
+}
+
+impl RemapTable {
+    /// Compute the table for the video frame of size `source`
+    /// scaled into the frame of size `target`.
+    pub fn new(undistort: &Undistort, source: (u32, u32), target: (u32, u32)) -> Self {
+        let (sw, sh) = (source.0 as f64, source.1 as f64);
+        let (tw, th) = (target.0 as usize, target.1 as usize);
+
+        let positions = (0..tw * th)
+            .map(|i| {
+                let (x, y) = ((i % tw) as f64 + 0.5, (i / tw) as f64 + 0.5);
+                let (u, v) = undistort.distort(x / tw as f64 * sw, y / th as f64 * sh);
+
+                ((0.0..=sw).contains(&u) && (0.0..=sh).contains(&v)).then(|| {
+                    (
+                        ((u - 0.5).max(0.0) * 256.0) as u32,
+                        ((v - 0.5).max(0.0) * 256.0) as u32,
+                    )
+                })
+            })
+            .collect();
+
+        Self {
+            source,
+            target,
+            positions,
+        }
+    }
+
+    /// Get the size of the video frame the table reads from.
+    pub fn source_size(&self) -> (u32, u32) {
+        self.source
+    }
+
+    /// Get the size of the corrected frame the table writes to.
+    pub fn target_size(&self) -> (u32, u32) {
+        self.target
+    }
+
+    /// Apply the correction from the RGBA `source` frame into the RGBA `target` frame
+    /// sampled with the `resample` filter.
+    pub fn apply(&self, source: &[u8], target: &mut [u8], resample: Resample) {
+        let size = (self.source.0 as usize, self.source.1 as usize);
+        let filter = Filter::new(resample, self.source, self.target, |x, y| Some((x, y)));
+
+        for (pixel, position) in target.chunks_exact_mut(4).zip(self.positions.iter()) {
+            match position {
+                Some((x, y)) => crate::utils::sample_rgba(
+                    source,
+                    size,
+                    *x as f64 / 256.0,
+                    *y as f64 / 256.0,
+                    &filter,
+                    pixel,
+                ),
+                None => pixel.fill(0),
+            }
+        }
+    }
+}
@@ -1,3 +1,202 @@
+use crate::Resample;
+
 pub fn video_size(video: &web_sys::HtmlVideoElement) -> (u32, u32) {
     (video.video_width(), video.video_height())
 }
+
+/// Resampling filter with the footprint of one output pixel in source texels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Filter {
+    pub resample: Resample,
+    pub footprint: (f64, f64),
+}
+
+impl Filter {
+    /// Estimate the footprint of one target pixel at the target center from `map`,
+    /// which maps normalized target coordinates to normalized source coordinates.
+    pub fn new(
+        resample: Resample,
+        source_size: (u32, u32),
+        target_size: (u32, u32),
+        map: impl Fn(f64, f64) -> Option<(f64, f64)>,
+    ) -> Self {
+        let (sw, sh) = (source_size.0 as f64, source_size.1 as f64);
+        let (dx, dy) = (
+            1.0 / target_size.0.max(1) as f64,
+            1.0 / target_size.1.max(1) as f64,
+        );
+
+        let distance =
+            |(x0, y0): (f64, f64), (x1, y1): (f64, f64)| ((x1 - x0) * sw).hypot((y1 - y0) * sh);
+
+        let footprint = map(0.5, 0.5)
+            .zip(map(0.5 + dx, 0.5))
+            .zip(map(0.5, 0.5 + dy))
+            .map(|((center, right), bottom)| (distance(center, right), distance(center, bottom)))
+            .unwrap_or((1.0, 1.0));
+
+        Self {
+            resample,
+            footprint,
+        }
+    }
+}
+
+fn cubic(x: f64) -> f64 {
+    let x = x.abs();
+    if x < 1.0 {
+        1.5 * x * x * x - 2.5 * x * x + 1.0
+    } else if x < 2.0 {
+        -0.5 * x * x * x + 2.5 * x * x - 4.0 * x + 2.0
+    } else {
+        0.0
+    }
+}
+
+fn lanczos(x: f64) -> f64 {
+    if x == 0.0 {
+        1.0
+    } else if x.abs() < 3.0 {
+        let x = std::f64::consts::PI * x;
+        3.0 * x.sin() * (x / 3.0).sin() / (x * x)
+    } else {
+        0.0
+    }
+}
+
+fn box_kernel(x: f64) -> f64 {
+    if x.abs() <= 0.5 {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// Sample the RGBA `source` at the texel position `(x, y)` with the `filter`,
+/// texel centers are at integer positions.
+pub(crate) fn sample_rgba(
+    source: &[u8],
+    (sw, sh): (usize, usize),
+    x: f64,
+    y: f64,
+    filter: &Filter,
+    pixel: &mut [u8],
+) {
+    let texel = |x: i64, y: i64| {
+        let (x, y) = (
+            x.clamp(0, sw as i64 - 1) as usize,
+            y.clamp(0, sh as i64 - 1) as usize,
+        );
+        &source[(y * sw + x) * 4..][..4]
+    };
+
+    let (kernel, radius): (fn(f64) -> f64, f64) = match filter.resample {
+        Resample::Nearest => {
+            pixel.copy_from_slice(texel(x.round() as i64, y.round() as i64));
+            return;
+        }
+        Resample::Area if filter.footprint.0 > 1.0 || filter.footprint.1 > 1.0 => (box_kernel, 0.5),
+        Resample::Bilinear | Resample::Area => {
+            let (x, y) = (x.clamp(0.0, (sw - 1) as f64), y.clamp(0.0, (sh - 1) as f64));
+            let (x0, y0) = (x.floor(), y.floor());
+            let (fx, fy) = (x - x0, y - y0);
+            let (x0, y0) = (x0 as i64, y0 as i64);
+
+            for (c, value) in pixel.iter_mut().enumerate() {
+                let top = texel(x0, y0)[c] as f64 * (1.0 - fx) + texel(x0 + 1, y0)[c] as f64 * fx;
+                let bottom =
+                    texel(x0, y0 + 1)[c] as f64 * (1.0 - fx) + texel(x0 + 1, y0 + 1)[c] as f64 * fx;
+                *value = (top * (1.0 - fy) + bottom * fy).round() as u8;
+            }
+            return;
+        }
+        Resample::Bicubic => (cubic, 2.0),
+        Resample::Lanczos => (lanczos, 3.0),
+    };
+
+    // widen the kernel when downscaling so every covered texel contributes
+    let (scale_x, scale_y) = (filter.footprint.0.max(1.0), filter.footprint.1.max(1.0));
+    let (rx, ry) = (radius * scale_x, radius * scale_y);
+
+    let mut sum = [0.0; 4];
+    let mut total = 0.0;
+    for ty in (y - ry).ceil() as i64..=(y + ry).floor() as i64 {
+        let wy = kernel((ty as f64 - y) / scale_y);
+        if wy == 0.0 {
+            continue;
+        }
+        for tx in (x - rx).ceil() as i64..=(x + rx).floor() as i64 {
+            let weight = wy * kernel((tx as f64 - x) / scale_x);
+            if weight == 0.0 {
+                continue;
+            }
+            for (sum, value) in sum.iter_mut().zip(texel(tx, ty)) {
+                *sum += weight * *value as f64;
+            }
+            total += weight;
+        }
+    }
+
+    for (value, sum) in pixel.iter_mut().zip(sum) {
+        *value = if total == 0.0 {
+            0
+        } else {
+            (sum / total).round().clamp(0.0, 255.0) as u8
+        };
+    }
+}
+
+/// Render the RGBA `source` into the RGBA `target` by inverse mapping each target pixel
+/// through `map` from normalized target coordinates to normalized source coordinates.
+///
+/// Pixels mapped outside of the source are transparent.
+pub(crate) fn remap_rgba(
+    source: &[u8],
+    source_size: (u32, u32),
+    target: &mut [u8],
+    target_size: (u32, u32),
+    resample: Resample,
+    map: impl Fn(f64, f64) -> Option<(f64, f64)>,
+) {
+    let (sw, sh) = (source_size.0 as usize, source_size.1 as usize);
+    let (tw, th) = (target_size.0 as usize, target_size.1 as usize);
+
+    if sw == 0 || sh == 0 {
+        target.fill(0);
+        return;
+    }
+
+    let filter = Filter::new(resample, source_size, target_size, &map);
+
+    for (i, pixel) in target.chunks_exact_mut(4).take(tw * th).enumerate() {
+        let (x, y) = ((i % tw) as f64 + 0.5, (i / tw) as f64 + 0.5);
+
+        match map(x / tw as f64, y / th as f64) {
+            Some((u, v)) if (0.0..=1.0).contains(&u) && (0.0..=1.0).contains(&v) => sample_rgba(
+                source,
+                (sw, sh),
+                u * sw as f64 - 0.5,
+                v * sh as f64 - 0.5,
+                &filter,
+                pixel,
+            ),
+            _ => pixel.fill(0),
+        }
+    }
+}
+
+/// Expand the raw frame with `channels` bytes per pixel into RGBA.
+pub(crate) fn expand_rgba(buffer: &[u8], channels: u32) -> Vec<u8> {
+    match channels {
+        4 => buffer.to_vec(),
+        _ => buffer
+            .chunks_exact(channels as usize)
+            .flat_map(|pixel| match pixel {
+                [l] => [*l, *l, *l, 255],
+                [l, a] => [*l, *l, *l, *a],
+                [r, g, b, ..] => [*r, *g, *b, 255],
+                _ => unreachable!(),
+            })
+            .collect(),
+    }
+}
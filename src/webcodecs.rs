@@ -0,0 +1,313 @@
+#[cfg(any(feature = "html", feature = "offscreen"))]
+use web_sys::{wasm_bindgen::JsValue, VideoFrameInit};
+use web_sys::{js_sys, VideoFrame, VideoFrameBufferInit};
+
+/// Canvas which can be wrapped into the video frame.
+#[cfg(any(feature = "html", feature = "offscreen"))]
+pub(crate) trait FrameCanvas {
+    fn video_frame(&self, init: &VideoFrameInit) -> Result<VideoFrame, JsValue>;
+}
+
+#[cfg(feature = "html")]
+impl FrameCanvas for web_sys::HtmlCanvasElement {
+    fn video_frame(&self, init: &VideoFrameInit) -> Result<VideoFrame, JsValue> {
+        VideoFrame::new_with_html_canvas_element_and_video_frame_init(self, init)
+    }
+}
+
+#[cfg(feature = "offscreen")]
+impl FrameCanvas for web_sys::OffscreenCanvas {
+    fn video_frame(&self, init: &VideoFrameInit) -> Result<VideoFrame, JsValue> {
+        VideoFrame::new_with_offscreen_canvas_and_video_frame_init(self, init)
+    }
+}
+
+/// Wrap the canvas content into the video frame with `timestamp` in seconds.
+#[cfg(any(feature = "html", feature = "offscreen"))]
+pub(crate) fn canvas_frame(
+    canvas: &impl FrameCanvas,
+    timestamp: f64,
+) -> Result<VideoFrame, js_sys::Error> {
+    let init = VideoFrameInit::new();
+    init.set_timestamp_f64(timestamp * 1e6);
+    Ok(canvas.video_frame(&init)?)
+}
+
+/// Wrap the raw frame with `channels` bytes per pixel into the RGBA video frame
+/// with `timestamp` in seconds.
+pub fn buffer_frame(
+    buffer: &[u8],
+    (width, height): (u32, u32),
+    channels: u32,
+    timestamp: f64,
+) -> Result<VideoFrame, js_sys::Error> {
+    let mut data = crate::utils::expand_rgba(buffer, channels);
+    let init = VideoFrameBufferInit::new_with_f64(
+        height,
+        width,
+        web_sys::VideoPixelFormat::Rgba,
+        timestamp * 1e6,
+    );
+    Ok(VideoFrame::new_with_u8_slice_and_video_frame_buffer_init(
+        &mut data, &init,
+    )?)
+}
+
+#[cfg(web_sys_unstable_apis)]
+pub use encoder::{EncodedChunk, EncoderOptions, FrameEncoder, VideoCodec};
+
+#[cfg(web_sys_unstable_apis)]
+mod encoder {
+    use std::{cell::RefCell, fmt::Display, rc::Rc};
+
+    use wasm_bindgen_futures::JsFuture;
+    use web_sys::{
+        js_sys,
+        wasm_bindgen::{closure::Closure, JsCast, JsValue},
+        EncodedVideoChunk, EncodedVideoChunkType, LatencyMode, VideoEncoder, VideoEncoderConfig,
+        VideoEncoderEncodeOptions, VideoEncoderInit, VideoEncoderSupport, VideoFrame,
+    };
+
+    use crate::BrowserVideoCapture;
+
+    /// Codec of the video encoder.
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+    #[non_exhaustive]
+    pub enum VideoCodec {
+        #[default]
+        Vp8,
+        Vp9,
+        Av1,
+        H264,
+    }
+
+    impl Display for VideoCodec {
+        /// Write the codec string with the widely supported profile and level.
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                VideoCodec::Vp8 => write!(f, "vp8"),
+                VideoCodec::Vp9 => write!(f, "vp09.00.10.08"),
+                VideoCodec::Av1 => write!(f, "av01.0.04M.08"),
+                VideoCodec::H264 => write!(f, "avc1.42001f"),
+            }
+        }
+    }
+
+    /// Options of the video encoder.
+    #[derive(Default, Debug, Clone, Copy, PartialEq)]
+    pub struct EncoderOptions {
+        pub codec: VideoCodec,
+        /// Target bitrate in bits per second.
+        pub bitrate: Option<u32>,
+        /// Expected frame rate.
+        pub framerate: Option<f64>,
+        /// Force the key frame every `keyframe_interval` frames.
+        pub keyframe_interval: Option<u32>,
+        /// Prefer the low latency over the quality.
+        pub realtime: bool,
+    }
+
+    impl EncoderOptions {
+        pub fn codec(mut self, value: VideoCodec) -> Self {
+            self.codec = value;
+            self
+        }
+
+        pub fn bitrate(mut self, value: u32) -> Self {
+            self.bitrate = Some(value);
+            self
+        }
+
+        pub fn framerate(mut self, value: f64) -> Self {
+            self.framerate = Some(value);
+            self
+        }
+
+        pub fn keyframe_interval(mut self, value: u32) -> Self {
+            self.keyframe_interval = Some(value);
+            self
+        }
+
+        pub fn realtime(mut self, value: bool) -> Self {
+            self.realtime = value;
+            self
+        }
+
+        fn config(&self, (width, height): (u32, u32)) -> VideoEncoderConfig {
+            let config = VideoEncoderConfig::new(&self.codec.to_string(), height, width);
+            if let Some(bitrate) = self.bitrate {
+                config.set_bitrate(bitrate);
+            }
+            if let Some(framerate) = self.framerate {
+                config.set_framerate(framerate);
+            }
+            config.set_latency_mode(if self.realtime {
+                LatencyMode::Realtime
+            } else {
+                LatencyMode::Quality
+            });
+            config
+        }
+    }
+
+    /// Encoded video data with timing in seconds.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct EncodedChunk {
+        pub data: Vec<u8>,
+        pub timestamp: f64,
+        pub duration: Option<f64>,
+        /// The chunk can be decoded without the previous ones.
+        pub key: bool,
+    }
+
+    impl From<&EncodedVideoChunk> for EncodedChunk {
+        fn from(chunk: &EncodedVideoChunk) -> Self {
+            let mut data = vec![0; chunk.byte_length() as usize];
+            chunk.copy_to_with_u8_slice(&mut data).unwrap();
+
+            Self {
+                data,
+                timestamp: chunk.timestamp() / 1e6,
+                duration: chunk.duration().map(|duration| duration / 1e6),
+                key: chunk.type_() == EncodedVideoChunkType::Key,
+            }
+        }
+    }
+
+    /// Sink of captured frames into the WebCodecs video encoder.
+    ///
+    /// Encoded chunks are queued until taken with [`FrameEncoder::take_chunks`].
+    #[derive(Debug)]
+    pub struct FrameEncoder {
+        encoder: VideoEncoder,
+        size: (u32, u32),
+        options: EncoderOptions,
+        frames: RefCell<u64>,
+        chunks: Rc<RefCell<Vec<EncodedChunk>>>,
+        error: Rc<RefCell<Option<JsValue>>>,
+        _output: Closure<dyn FnMut(EncodedVideoChunk)>,
+        _error: Closure<dyn FnMut(JsValue)>,
+    }
+
+    impl FrameEncoder {
+        /// Create and configure the encoder of frames of `size`.
+        pub fn new(size: (u32, u32), options: EncoderOptions) -> Result<Self, js_sys::Error> {
+            let chunks = Rc::new(RefCell::new(Vec::new()));
+            let error = Rc::new(RefCell::new(None));
+
+            let output = {
+                let chunks = chunks.clone();
+                Closure::<dyn FnMut(EncodedVideoChunk)>::new(move |chunk: EncodedVideoChunk| {
+                    chunks.borrow_mut().push(EncodedChunk::from(&chunk));
+                })
+            };
+            let on_error = {
+                let error = error.clone();
+                Closure::<dyn FnMut(JsValue)>::new(move |value: JsValue| {
+                    *error.borrow_mut() = Some(value);
+                })
+            };
+
+            let encoder = VideoEncoder::new(&VideoEncoderInit::new(
+                on_error.as_ref().unchecked_ref(),
+                output.as_ref().unchecked_ref(),
+            ))?;
+            encoder.configure(&options.config(size))?;
+
+            Ok(Self {
+                encoder,
+                size,
+                options,
+                frames: RefCell::new(0),
+                chunks,
+                error,
+                _output: output,
+                _error: on_error,
+            })
+        }
+
+        /// Check if the browser can encode frames of `size` with the options.
+        pub async fn is_supported(size: (u32, u32), options: EncoderOptions) -> bool {
+            let config = options.config(size);
+            match JsFuture::from(VideoEncoder::is_config_supported(&config)).await {
+                Ok(value) => value
+                    .unchecked_into::<VideoEncoderSupport>()
+                    .get_supported()
+                    .unwrap_or(false),
+                Err(_) => false,
+            }
+        }
+
+        /// Get the frame size.
+        pub fn size(&self) -> (u32, u32) {
+            self.size
+        }
+
+        /// Get the number of encoded frames.
+        pub fn frames(&self) -> u64 {
+            *self.frames.borrow()
+        }
+
+        /// Get the underlying encoder.
+        pub fn encoder(&self) -> &VideoEncoder {
+            &self.encoder
+        }
+
+        /// Encode the video frame, the key frame is forced with `key_frame`
+        /// or by the key frame interval.
+        pub fn encode(&self, frame: &VideoFrame, key_frame: bool) -> Result<(), js_sys::Error> {
+            if let Some(error) = self.error.borrow_mut().take() {
+                return Err(error.into());
+            }
+
+            let mut frames = self.frames.borrow_mut();
+            let interval = self.options.keyframe_interval.unwrap_or(0) as u64;
+            let key_frame = key_frame || (interval > 0 && frames.is_multiple_of(interval));
+
+            let options = VideoEncoderEncodeOptions::new();
+            options.set_key_frame(key_frame);
+            self.encoder.encode_with_options(frame, &options)?;
+
+            *frames += 1;
+            Ok(())
+        }
+
+        /// Encode the frame retrieved from the capture taken at `timestamp` in seconds.
+        pub fn encode_capture(
+            &self,
+            capture: &(impl BrowserVideoCapture + ?Sized),
+            timestamp: f64,
+            key_frame: bool,
+        ) -> Result<(), js_sys::Error> {
+            let frame = super::buffer_frame(
+                &capture.packed_data(),
+                capture.capture_size(),
+                capture.channels_count(),
+                timestamp,
+            )?;
+            let result = self.encode(&frame, key_frame);
+            frame.close();
+            result
+        }
+
+        /// Wait until all queued frames are encoded.
+        pub async fn flush(&self) -> Result<(), js_sys::Error> {
+            JsFuture::from(self.encoder.flush()).await?;
+            match self.error.borrow_mut().take() {
+                Some(error) => Err(error.into()),
+                None => Ok(()),
+            }
+        }
+
+        /// Take the encoded chunks produced so far.
+        pub fn take_chunks(&self) -> Vec<EncodedChunk> {
+            self.chunks.borrow_mut().drain(..).collect()
+        }
+    }
+
+    impl Drop for FrameEncoder {
+        fn drop(&mut self) {
+            let _ = self.encoder.close();
+        }
+    }
+}
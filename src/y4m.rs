@@ -0,0 +1,430 @@
+use std::io::{self, BufRead, Write};
+
+use crate::BrowserVideoCapture;
+
+/// Chroma subsampling of the Y4M stream.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Y4mChroma {
+    /// Chroma planes at half the width and height.
+    #[default]
+    C420,
+    /// Chroma planes at the full resolution.
+    C444,
+}
+
+impl Y4mChroma {
+    /// Get the size of one chroma plane for the frame of size `(width, height)`.
+    pub fn plane_size(&self, (width, height): (u32, u32)) -> (u32, u32) {
+        match self {
+            Y4mChroma::C420 => (width.div_ceil(2), height.div_ceil(2)),
+            Y4mChroma::C444 => (width, height),
+        }
+    }
+
+    /// Get the size of one frame in bytes, saturating at `usize::MAX`.
+    pub fn frame_size(&self, size: (u32, u32)) -> usize {
+        self.checked_frame_size(size).unwrap_or(usize::MAX)
+    }
+
+    /// Get the size of one frame in bytes, `None` when it cannot be allocated.
+    fn checked_frame_size(&self, (width, height): (u32, u32)) -> Option<usize> {
+        let (cw, ch) = self.plane_size((width, height));
+        let luma = (width as usize).checked_mul(height as usize)?;
+        let chroma = (cw as usize).checked_mul(ch as usize)?.checked_mul(2)?;
+        luma.checked_add(chroma)
+            .filter(|&size| size <= isize::MAX as usize)
+    }
+
+    fn tag(&self) -> &'static str {
+        match self {
+            Y4mChroma::C420 => "420jpeg",
+            Y4mChroma::C444 => "444",
+        }
+    }
+
+    fn parse(tag: &str) -> Option<Self> {
+        match tag {
+            "420" | "420jpeg" | "420mpeg2" | "420paldv" => Some(Y4mChroma::C420),
+            "444" => Some(Y4mChroma::C444),
+            _ => None,
+        }
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn invalid_input(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+/// Check that the buffer holds exactly the frame of `size` with `channels` bytes per pixel.
+fn check_buffer(buffer: &[u8], (width, height): (u32, u32), channels: u32) -> io::Result<()> {
+    if !(1..=4).contains(&channels) {
+        return Err(invalid_input("Unsupported channels count"));
+    }
+    let expected = (width as usize)
+        .checked_mul(height as usize)
+        .and_then(|area| area.checked_mul(channels as usize));
+    if expected != Some(buffer.len()) {
+        return Err(invalid_input("Frame size does not match the buffer"));
+    }
+    Ok(())
+}
+
+/// Convert the pixel of `channels` bytes to BT.601 limited range YCbCr.
+fn ycbcr(pixel: &[u8]) -> (f64, f64, f64) {
+    let (r, g, b) = match pixel.len() {
+        1 | 2 => (pixel[0], pixel[0], pixel[0]),
+        _ => (pixel[0], pixel[1], pixel[2]),
+    };
+    let (r, g, b) = (r as f64, g as f64, b as f64);
+
+    (
+        16.0 + (65.481 * r + 128.553 * g + 24.966 * b) / 255.0,
+        128.0 + (-37.797 * r - 74.203 * g + 112.0 * b) / 255.0,
+        128.0 + (112.0 * r - 93.786 * g - 18.214 * b) / 255.0,
+    )
+}
+
+/// Convert the BT.601 limited range YCbCr to RGB.
+fn rgb(y: u8, cb: u8, cr: u8) -> [u8; 3] {
+    let (y, cb, cr) = (
+        (y as f64 - 16.0) * 255.0 / 219.0,
+        (cb as f64 - 128.0) * 255.0 / 224.0,
+        (cr as f64 - 128.0) * 255.0 / 224.0,
+    );
+
+    [
+        y + 1.402 * cr,
+        y - 0.344136 * cb - 0.714136 * cr,
+        y + 1.772 * cb,
+    ]
+    .map(|value| value.round().clamp(0.0, 255.0) as u8)
+}
+
+/// Convert the raw frame with `channels` bytes per pixel into the planar YCbCr frame.
+///
+/// The alpha channel is dropped, the gray frames have neutral chroma.
+/// Fails with [`io::ErrorKind::InvalidInput`] when the buffer does not hold the frame.
+pub fn frame_to_ycbcr(
+    buffer: &[u8],
+    (width, height): (u32, u32),
+    channels: u32,
+    chroma: Y4mChroma,
+) -> io::Result<Vec<u8>> {
+    check_buffer(buffer, (width, height), channels)?;
+    let (w, h, channels) = (width as usize, height as usize, channels as usize);
+    let pixels: Vec<(f64, f64, f64)> = buffer.chunks_exact(channels).map(ycbcr).collect();
+
+    let (cw, ch) = chroma.plane_size((width, height));
+    let (cw, ch) = (cw as usize, ch as usize);
+    let mut frame = Vec::with_capacity(chroma.frame_size((width, height)));
+    frame.extend(pixels.iter().map(|&(y, _, _)| y.round() as u8));
+
+    let block = match chroma {
+        Y4mChroma::C420 => 2,
+        Y4mChroma::C444 => 1,
+    };
+
+    // average the chroma of every block of pixels clipped by the frame
+    let mut cb = Vec::with_capacity(cw * ch);
+    let mut cr = Vec::with_capacity(cw * ch);
+    for cy in 0..ch {
+        for cx in 0..cw {
+            let (mut sum_cb, mut sum_cr, mut count) = (0.0, 0.0, 0.0);
+            for y in (cy * block)..((cy + 1) * block).min(h) {
+                for x in (cx * block)..((cx + 1) * block).min(w) {
+                    let (_, b, r) = pixels[y * w + x];
+                    sum_cb += b;
+                    sum_cr += r;
+                    count += 1.0;
+                }
+            }
+            cb.push((sum_cb / count).round() as u8);
+            cr.push((sum_cr / count).round() as u8);
+        }
+    }
+
+    frame.extend(cb);
+    frame.extend(cr);
+    Ok(frame)
+}
+
+/// Convert the planar YCbCr frame into the RGBA frame,
+/// fails with [`io::ErrorKind::InvalidInput`] when the frame does not hold the planes.
+pub fn ycbcr_to_rgba(
+    frame: &[u8],
+    (width, height): (u32, u32),
+    chroma: Y4mChroma,
+) -> io::Result<Vec<u8>> {
+    if chroma.checked_frame_size((width, height)) != Some(frame.len()) {
+        return Err(invalid_input("Frame size does not match the planes"));
+    }
+    let (w, h) = (width as usize, height as usize);
+    let (cw, ch) = chroma.plane_size((width, height));
+    let plane = (cw * ch) as usize;
+    let (luma, chroma_planes) = frame.split_at(w * h);
+    let (cb, cr) = chroma_planes.split_at(plane);
+
+    let block = match chroma {
+        Y4mChroma::C420 => 2,
+        Y4mChroma::C444 => 1,
+    };
+
+    Ok((0..w * h)
+        .flat_map(|i| {
+            let (x, y) = (i % w, i / w);
+            let c = (y / block) * cw as usize + x / block;
+            let [r, g, b] = rgb(luma[i], cb[c], cr[c]);
+            [r, g, b, 255]
+        })
+        .collect())
+}
+
+/// Writer of captured frames into the YUV4MPEG2 stream.
+///
+/// The stream header is written with the first frame.
+#[derive(Debug)]
+pub struct Y4mWriter<W: Write> {
+    writer: W,
+    size: (u32, u32),
+    rate: (u32, u32),
+    chroma: Y4mChroma,
+    frames: u64,
+    start: Option<f64>,
+    last: Option<Vec<u8>>,
+}
+
+impl<W: Write> Y4mWriter<W> {
+    /// Create the writer of frames of `size` with the frame `rate` as `(numerator, denominator)`.
+    pub fn new(writer: W, size: (u32, u32), rate: (u32, u32)) -> Self {
+        Self {
+            writer,
+            size,
+            rate,
+            chroma: Y4mChroma::default(),
+            frames: 0,
+            start: None,
+            last: None,
+        }
+    }
+
+    /// Create the writer for frames of the current capture size.
+    pub fn for_capture(
+        writer: W,
+        capture: &(impl BrowserVideoCapture + ?Sized),
+        rate: (u32, u32),
+    ) -> Self {
+        Self::new(writer, capture.capture_size(), rate)
+    }
+
+    /// Set the chroma subsampling.
+    pub fn chroma(mut self, chroma: Y4mChroma) -> Self {
+        self.chroma = chroma;
+        self
+    }
+
+    /// Get the number of written frames.
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// Get the frame size.
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    fn write_header(&mut self) -> io::Result<()> {
+        writeln!(
+            self.writer,
+            "YUV4MPEG2 W{} H{} F{}:{} Ip A1:1 C{} XCOLORRANGE=LIMITED",
+            self.size.0,
+            self.size.1,
+            self.rate.0,
+            self.rate.1,
+            self.chroma.tag()
+        )
+    }
+
+    fn write_planes(&mut self, frame: &[u8]) -> io::Result<()> {
+        if self.frames == 0 {
+            self.write_header()?;
+        }
+        self.writer.write_all(b"FRAME\n")?;
+        self.writer.write_all(frame)?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Append the raw frame with `channels` bytes per pixel.
+    pub fn write(&mut self, buffer: &[u8], channels: u32) -> io::Result<()> {
+        let frame = frame_to_ycbcr(buffer, self.size, channels, self.chroma)?;
+        self.write_planes(&frame)?;
+        self.last = Some(frame);
+        Ok(())
+    }
+
+    /// Append the raw frame taken at `timestamp` in seconds, such as
+    /// [`HtmlMediaElement::current_time`](web_sys::HtmlMediaElement::current_time).
+    ///
+    /// The stream has a constant frame rate, so the previous frame is repeated
+    /// to fill the gap before the timestamp, and the frame is dropped when
+    /// its slot is already taken.
+    pub fn write_at(&mut self, buffer: &[u8], channels: u32, timestamp: f64) -> io::Result<()> {
+        let start = *self.start.get_or_insert(timestamp);
+        let (numerator, denominator) = (self.rate.0 as f64, self.rate.1.max(1) as f64);
+        let slot = ((timestamp - start) * numerator / denominator).round();
+
+        if slot < self.frames as f64 {
+            return Ok(());
+        }
+        // the gap is not filled for the frame which cannot be written
+        check_buffer(buffer, self.size, channels)?;
+
+        if let Some(last) = self.last.take() {
+            let mut result = Ok(());
+            while result.is_ok() && (self.frames as f64) < slot {
+                result = self.write_planes(&last);
+            }
+            // keep the frame to repeat after the failed write
+            self.last = Some(last);
+            result?;
+        }
+
+        self.write(buffer, channels)
+    }
+
+    /// Append the frame retrieved from the capture.
+    pub fn write_capture(
+        &mut self,
+        capture: &(impl BrowserVideoCapture + ?Sized),
+    ) -> io::Result<()> {
+        if capture.capture_size() != self.size {
+            return Err(invalid_input("Capture size does not match the stream"));
+        }
+        self.write(&capture.packed_data(), capture.channels_count())
+    }
+
+    /// Append the frame retrieved from the capture taken at `timestamp` in seconds.
+    pub fn write_capture_at(
+        &mut self,
+        capture: &(impl BrowserVideoCapture + ?Sized),
+        timestamp: f64,
+    ) -> io::Result<()> {
+        if capture.capture_size() != self.size {
+            return Err(invalid_input("Capture size does not match the stream"));
+        }
+        self.write_at(&capture.packed_data(), capture.channels_count(), timestamp)
+    }
+
+    /// Flush the underlying writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Get the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Reader of the YUV4MPEG2 stream.
+#[derive(Debug)]
+pub struct Y4mReader<R: BufRead> {
+    reader: R,
+    size: (u32, u32),
+    rate: (u32, u32),
+    chroma: Y4mChroma,
+}
+
+impl<R: BufRead> Y4mReader<R> {
+    /// Read the stream header.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+
+        let mut parameters = header.trim_end().split(' ');
+        if parameters.next() != Some("YUV4MPEG2") {
+            return Err(invalid_data("Not a YUV4MPEG2 stream"));
+        }
+
+        let (mut width, mut height) = (None, None);
+        let mut rate = (30, 1);
+        let mut chroma = Y4mChroma::C420;
+
+        for parameter in parameters.filter(|p| !p.is_empty()) {
+            let Some((tag, value)) = parameter.split_at_checked(1) else {
+                continue;
+            };
+            match tag {
+                "W" => width = value.parse().ok(),
+                "H" => height = value.parse().ok(),
+                "F" => {
+                    rate = value
+                        .split_once(':')
+                        .and_then(|(n, d)| Some((n.parse().ok()?, d.parse().ok()?)))
+                        .ok_or_else(|| invalid_data("Invalid frame rate"))?
+                }
+                "C" => {
+                    chroma = Y4mChroma::parse(value)
+                        .ok_or_else(|| invalid_data("Unsupported chroma subsampling"))?
+                }
+                _ => {}
+            }
+        }
+
+        let size = width
+            .zip(height)
+            .ok_or_else(|| invalid_data("Missing frame size"))?;
+        if chroma.checked_frame_size(size).is_none() {
+            return Err(invalid_data("Frame size is too large"));
+        }
+
+        Ok(Self {
+            reader,
+            size,
+            rate,
+            chroma,
+        })
+    }
+
+    /// Get the frame size.
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    /// Get the frame rate as `(numerator, denominator)`.
+    pub fn rate(&self) -> (u32, u32) {
+        self.rate
+    }
+
+    /// Get the chroma subsampling.
+    pub fn chroma(&self) -> Y4mChroma {
+        self.chroma
+    }
+
+    /// Read the next planar YCbCr frame, `None` at the end of the stream.
+    pub fn read_frame(&mut self) -> io::Result<Option<Vec<u8>>> {
+        let mut header = String::new();
+        if self.reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        if !header.starts_with("FRAME") {
+            return Err(invalid_data("Missing frame header"));
+        }
+
+        let mut frame = vec![0; self.chroma.frame_size(self.size)];
+        self.reader.read_exact(&mut frame)?;
+        Ok(Some(frame))
+    }
+
+    /// Read the next frame converted to RGBA, `None` at the end of the stream.
+    pub fn read_rgba(&mut self) -> io::Result<Option<Vec<u8>>> {
+        self.read_frame()?
+            .map(|frame| ycbcr_to_rgba(&frame, self.size, self.chroma))
+            .transpose()
+    }
+}
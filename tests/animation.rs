@@ -0,0 +1,93 @@
+#![cfg(any(feature = "gif", feature = "apng"))]
+
+use browser_video_capture::AnimationRecorder;
+
+fn frame(color: [u8; 4]) -> Vec<u8> {
+    color.repeat(4 * 3)
+}
+
+fn recorder() -> AnimationRecorder {
+    let mut recorder = AnimationRecorder::new(3, 10.0);
+    assert!(recorder.push(&frame([255, 0, 0, 255]), (4, 3), 4, 1.0));
+    // not due by the rate
+    assert!(!recorder.push(&frame([0, 0, 0, 255]), (4, 3), 4, 1.05));
+    assert!(recorder.push(&frame([0, 255, 0, 255]), (4, 3), 4, 1.2));
+    // size differs from the first frame
+    assert!(!recorder.push(&[0; 4], (1, 1), 4, 1.3));
+    // buffer shorter than the frame or without channels
+    assert!(!recorder.push(&[0; 4], (4, 3), 4, 1.3));
+    assert!(!recorder.push(&[], (4, 3), 0, 1.3));
+    assert!(recorder.push(&[0, 0, 255].repeat(4 * 3), (4, 3), 3, 1.4));
+    assert!(recorder.is_full());
+    assert!(!recorder.push(&frame([0, 0, 0, 255]), (4, 3), 4, 2.0));
+    recorder
+}
+
+#[test]
+fn push_follows_rate_and_limit() {
+    let recorder = recorder();
+    let delays: Vec<f64> = recorder.frames().iter().map(|frame| frame.delay).collect();
+
+    assert_eq!(recorder.size(), Some((4, 3)));
+    assert_eq!(recorder.frames()[2].data, frame([0, 0, 255, 255]));
+    for (delay, expected) in delays.iter().zip([0.2, 0.2, 0.1].iter()) {
+        assert!((delay - expected).abs() < 1e-9);
+    }
+}
+
+#[cfg(feature = "gif")]
+#[test]
+fn gif_frames_and_delays() {
+    for dither in [false, true].iter() {
+        let gif = recorder().dither(*dither).encode_gif().unwrap();
+
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(&gif[..]).unwrap();
+
+        let mut delays = Vec::new();
+        for expected in [[255u8, 0, 0], [0, 255, 0], [0, 0, 255]].iter() {
+            let frame = decoder.read_next_frame().unwrap().unwrap();
+            delays.push(frame.delay);
+            for (value, expected) in frame.buffer[4 * 5..4 * 5 + 3].iter().zip(expected.iter()) {
+                assert!((*value as i32 - *expected as i32).abs() <= 8);
+            }
+        }
+
+        assert!(decoder.read_next_frame().unwrap().is_none());
+        assert_eq!(delays, [20, 20, 10]);
+    }
+}
+
+#[cfg(feature = "gif")]
+#[test]
+fn gif_rejects_oversized_frames() {
+    let mut recorder = AnimationRecorder::new(1, 10.0);
+    assert!(recorder.push(&[0; 65536 * 4], (65536, 1), 4, 0.0));
+    let error = recorder.encode_gif().unwrap_err();
+
+    assert!(error
+        .get_ref()
+        .is_some_and(|error| error.is::<image::ImageError>()));
+}
+
+#[cfg(feature = "apng")]
+#[test]
+fn apng_frames_are_lossless() {
+    let apng = recorder().encode_apng().unwrap();
+    let mut reader = png::Decoder::new(std::io::Cursor::new(apng))
+        .read_info()
+        .unwrap();
+    assert_eq!(reader.info().animation_control.unwrap().num_frames, 3);
+
+    let mut buffer = vec![0; reader.output_buffer_size().unwrap()];
+    let mut delays = Vec::new();
+    for _ in 0..3 {
+        reader.next_frame(&mut buffer).unwrap();
+        let control = reader.info().frame_control.unwrap();
+        delays.push((control.delay_num, control.delay_den));
+    }
+
+    assert_eq!(delays, [(200, 1000), (200, 1000), (100, 1000)]);
+    assert_eq!(buffer, frame([0, 0, 255, 255]));
+}
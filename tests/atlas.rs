@@ -0,0 +1,105 @@
+use browser_video_capture::{CaptureMode, Mosaic, Pyramid, Rect};
+
+#[test]
+fn pyramid_levels_layout() {
+    let pyramid = Pyramid::new(64, 48, 3);
+
+    assert_eq!(pyramid.len(), 3);
+    assert_eq!(
+        pyramid.levels(),
+        [
+            Rect::new(0, 0, 64, 48),
+            Rect::new(64, 0, 32, 24),
+            Rect::new(64, 24, 16, 12),
+        ]
+    );
+    assert_eq!(pyramid.size(), (96, 48));
+    assert_eq!(pyramid.level(3), None);
+}
+
+#[test]
+fn pyramid_single_level_is_the_frame() {
+    let pyramid = Pyramid::new(5, 3, 0);
+
+    assert_eq!(pyramid.levels(), [Rect::new(0, 0, 5, 3)]);
+    assert_eq!(pyramid.size(), (5, 3));
+}
+
+#[test]
+fn pyramid_copies_and_packs_levels() {
+    let pyramid = Pyramid::new(4, 2, 2);
+    let (width, height) = pyramid.size();
+    assert_eq!((width, height), (6, 2));
+
+    // every pixel holds its index in the atlas
+    let atlas: Vec<u8> = (0..(width * height) as u8).collect();
+
+    assert_eq!(
+        pyramid.copy_level(&atlas, 0, 1).unwrap(),
+        [0, 1, 2, 3, 6, 7, 8, 9]
+    );
+    assert_eq!(pyramid.copy_level(&atlas, 1, 1).unwrap(), [4, 5]);
+
+    assert_eq!(pyramid.packed_offsets(1), [0, 8]);
+    assert_eq!(pyramid.pack(&atlas, 1), [0, 1, 2, 3, 6, 7, 8, 9, 4, 5]);
+}
+
+#[test]
+fn rect_copy_with_channels() {
+    let buffer: Vec<u8> = (0..3 * 2 * 2).collect();
+    let mut target = [0; 4];
+
+    Rect::new(1, 1, 2, 1).copy_from(&buffer, 3, 2, &mut target);
+    assert_eq!(target, [8, 9, 10, 11]);
+    assert_eq!(Rect::new(1, 1, 2, 1).offset(6, 2), 8);
+}
+
+#[test]
+fn mosaic_grid_layout() {
+    let mosaic = Mosaic::grid(2, 2, 32, 24);
+
+    assert_eq!(mosaic.len(), 4);
+    assert_eq!(mosaic.size(), (64, 48));
+    assert_eq!(mosaic.tile(3), Some(Rect::new(32, 24, 32, 24)));
+    assert_eq!(mosaic.tile(4), None);
+}
+
+#[test]
+fn mosaic_placement_per_mode() {
+    let mosaic = Mosaic::grid(2, 1, 32, 24);
+
+    assert_eq!(
+        mosaic.placement(1, (640, 480), CaptureMode::Fill),
+        Some(((32, 0, 32, 24), Rect::new(32, 0, 32, 24)))
+    );
+    assert_eq!(
+        mosaic.placement(1, (640, 480), CaptureMode::Adjust),
+        Some(((32, 0, 32, 24), Rect::new(32, 0, 32, 24)))
+    );
+    // the source covers the tile and the sides are clipped
+    assert_eq!(
+        mosaic.placement(1, (640, 360), CaptureMode::Pinhole),
+        Some(((27, 0, 43, 24), Rect::new(32, 0, 32, 24)))
+    );
+    assert_eq!(
+        mosaic.placement(1, (16, 16), CaptureMode::Put(8, 4)),
+        Some(((40, 4, 16, 16), Rect::new(40, 4, 16, 16)))
+    );
+    // the part outside of the tile is clipped
+    assert_eq!(
+        mosaic.placement(0, (16, 16), CaptureMode::Put(24, -8)),
+        Some(((24, -8, 16, 16), Rect::new(24, 0, 8, 8)))
+    );
+    assert_eq!(
+        mosaic.placement(0, (16, 16), CaptureMode::Put(40, 0)).unwrap().1,
+        Rect::default()
+    );
+}
+
+#[test]
+fn mosaic_copies_tile() {
+    let mosaic = Mosaic::grid(2, 1, 2, 2);
+    let atlas: Vec<u8> = (0..8).collect();
+
+    assert_eq!(mosaic.copy_tile(&atlas, 1, 1).unwrap(), [2, 3, 6, 7]);
+}
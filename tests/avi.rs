@@ -0,0 +1,90 @@
+use std::io::Cursor;
+
+use browser_video_capture::AviWriter;
+
+fn u32_at(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        data[offset],
+        data[offset + 1],
+        data[offset + 2],
+        data[offset + 3],
+    ])
+}
+
+/// Find the offset of the data of the first chunk with the `id` after `start`.
+fn find(data: &[u8], id: &[u8], start: usize) -> usize {
+    start
+        + data[start..]
+            .windows(4)
+            .position(|window| window == id)
+            .unwrap()
+        + 8
+}
+
+#[test]
+fn riff_structure_and_index() {
+    let mut writer = AviWriter::new(Cursor::new(Vec::new()), (4, 2), (30, 1)).unwrap();
+    writer.write_jpeg(b"abc").unwrap();
+    writer.write_jpeg(b"defg").unwrap();
+    let data = writer.finish().unwrap().into_inner();
+
+    assert_eq!(&data[..4], b"RIFF");
+    assert_eq!(u32_at(&data, 4) as usize, data.len() - 8);
+    assert_eq!(&data[8..12], b"AVI ");
+
+    let avih = find(&data, b"avih", 0);
+    assert_eq!(u32_at(&data, avih), 33333);
+    assert_eq!(u32_at(&data, avih + 16), 2);
+    assert_eq!((u32_at(&data, avih + 32), u32_at(&data, avih + 36)), (4, 2));
+
+    let movi = find(&data, b"movi", 0) - 8;
+    assert_eq!(&data[movi + 4..movi + 12], b"00dc\x03\x00\x00\x00");
+    assert_eq!(&data[movi + 12..movi + 15], b"abc");
+
+    let idx1 = find(&data, b"idx1", movi);
+    assert_eq!(u32_at(&data, idx1 - 4), 32);
+    // odd chunks are padded to the even size
+    assert_eq!(u32_at(&data, idx1 + 16 + 8), 4 + 8 + 4);
+    assert_eq!(u32_at(&data, idx1 + 16 + 12), 4);
+    assert_eq!(data.len(), idx1 + 32);
+}
+
+#[test]
+fn timestamps_fill_gaps_with_empty_chunks() {
+    let mut writer = AviWriter::new(Cursor::new(Vec::new()), (1, 1), (10, 1)).unwrap();
+    writer.write_jpeg_at(b"aa", 1.0).unwrap();
+    writer.write_jpeg_at(b"bb", 1.3).unwrap();
+    writer.write_jpeg_at(b"cc", 1.32).unwrap();
+    assert_eq!(writer.frames(), 4);
+
+    let data = writer.finish().unwrap().into_inner();
+    let idx1 = find(&data, b"idx1", 0);
+    let sizes: Vec<u32> = (0..4).map(|i| u32_at(&data, idx1 + i * 16 + 12)).collect();
+    let flags: Vec<u32> = (0..4).map(|i| u32_at(&data, idx1 + i * 16 + 4)).collect();
+
+    assert_eq!(sizes, [2, 0, 0, 2]);
+    assert_eq!(flags, [0x10, 0, 0, 0x10]);
+}
+
+#[cfg(feature = "jpeg")]
+#[test]
+fn frames_are_decodable_jpeg() {
+    let mut writer = AviWriter::new(Cursor::new(Vec::new()), (8, 8), (25, 1))
+        .unwrap()
+        .quality(95);
+    writer
+        .write_at(&[200, 100, 50, 255].repeat(64), 4, 0.0)
+        .unwrap();
+    let data = writer.finish().unwrap().into_inner();
+
+    let movi = find(&data, b"movi", 0) - 8;
+    let size = u32_at(&data, movi + 8) as usize;
+    let frame = image::load_from_memory(&data[movi + 12..movi + 12 + size])
+        .unwrap()
+        .to_rgb8();
+
+    assert_eq!(frame.dimensions(), (8, 8));
+    for (value, expected) in frame.get_pixel(4, 4).0.iter().zip([200i32, 100, 50].iter()) {
+        assert!((*value as i32 - expected).abs() <= 4);
+    }
+}
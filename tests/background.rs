@@ -0,0 +1,46 @@
+use browser_video_capture::{BackgroundModel, BackgroundOptions};
+
+const SIZE: (u32, u32) = (4, 2);
+
+#[test]
+fn first_frame_becomes_background() {
+    let mut model = BackgroundModel::new(BackgroundOptions::default());
+    assert_eq!(model.update(&[50; 8], SIZE, 1), 0.0);
+    assert_eq!(model.mask(), [0; 8]);
+
+    // small noise stays in the background
+    let frame = [52, 48, 50, 51, 49, 50, 53, 47];
+    assert_eq!(model.update(&frame, SIZE, 1), 0.0);
+}
+
+#[test]
+fn object_is_foreground_until_learned() {
+    let options = BackgroundOptions::default()
+        .learning_rate(0.5)
+        .threshold(2.0);
+    let mut model = BackgroundModel::new(options);
+    model.update(&[50; 8], SIZE, 1);
+
+    let mut frame = [50; 8];
+    frame[..2].copy_from_slice(&[250, 250]);
+    assert_eq!(model.update(&frame, SIZE, 1), 0.25);
+    assert_eq!(model.mask()[..3], [255, 255, 0]);
+
+    // the static object fades into the background
+    let ratios: Vec<f64> = (0..10).map(|_| model.update(&frame, SIZE, 1)).collect();
+    assert_eq!(*ratios.last().unwrap(), 0.0);
+}
+
+#[test]
+fn reset_and_resize_relearn_background() {
+    let mut model = BackgroundModel::new(BackgroundOptions::default());
+    model.update(&[0; 8], SIZE, 1);
+    assert_eq!(model.update(&[255; 8], SIZE, 1), 1.0);
+
+    model.reset();
+    assert_eq!(model.update(&[255; 8], SIZE, 1), 0.0);
+
+    // RGBA frame of the new size
+    assert_eq!(model.update(&[0; 16], (2, 2), 4), 0.0);
+    assert_eq!(model.mask().len(), 4);
+}
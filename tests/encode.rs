@@ -0,0 +1,81 @@
+#![cfg(any(feature = "png", feature = "jpeg", feature = "webp", feature = "bmp"))]
+
+use image::ColorType;
+
+#[cfg(any(feature = "png", feature = "webp", feature = "bmp"))]
+fn gradient(width: u32, height: u32, channels: u32) -> Vec<u8> {
+    (0..width * height * channels)
+        .map(|i| (i * 7 % 256) as u8)
+        .collect()
+}
+
+#[cfg(feature = "png")]
+#[test]
+fn png_roundtrip_keeps_channels() {
+    use browser_video_capture::encode_png;
+
+    for (channels, color) in [
+        (1, ColorType::L8),
+        (2, ColorType::La8),
+        (3, ColorType::Rgb8),
+        (4, ColorType::Rgba8),
+    ]
+    .iter()
+    {
+        let buffer = gradient(5, 3, *channels);
+        let encoded = encode_png(&buffer, (5, 3), *color).unwrap();
+        let decoded = image::load_from_memory(&encoded).unwrap();
+
+        assert_eq!(decoded.color(), *color);
+        assert_eq!(decoded.as_bytes(), &buffer[..]);
+    }
+}
+
+#[cfg(feature = "png")]
+#[test]
+fn buffer_size_mismatch_is_error() {
+    use browser_video_capture::encode_png;
+
+    assert!(encode_png(&[0; 10], (2, 2), ColorType::Rgba8).is_err());
+}
+
+#[cfg(feature = "jpeg")]
+#[test]
+fn jpeg_drops_alpha() {
+    use browser_video_capture::encode_jpeg;
+
+    let buffer = [128u8, 64, 32, 255].repeat(16 * 16);
+    let encoded = encode_jpeg(&buffer, (16, 16), ColorType::Rgba8, 90).unwrap();
+    let decoded = image::load_from_memory(&encoded).unwrap();
+
+    assert_eq!(decoded.color(), ColorType::Rgb8);
+    assert_eq!((decoded.width(), decoded.height()), (16, 16));
+    let pixel = decoded.to_rgb8().get_pixel(8, 8).0;
+    for (value, expected) in pixel.iter().zip([128i32, 64, 32].iter()) {
+        assert!((*value as i32 - expected).abs() <= 4);
+    }
+}
+
+#[cfg(feature = "webp")]
+#[test]
+fn webp_is_lossless() {
+    use browser_video_capture::encode_webp;
+
+    let buffer = gradient(7, 4, 4);
+    let encoded = encode_webp(&buffer, (7, 4), ColorType::Rgba8).unwrap();
+    let decoded = image::load_from_memory(&encoded).unwrap();
+
+    assert_eq!(decoded.to_rgba8().into_raw(), buffer);
+}
+
+#[cfg(feature = "bmp")]
+#[test]
+fn bmp_roundtrip() {
+    use browser_video_capture::encode_bmp;
+
+    let buffer = gradient(3, 2, 3);
+    let encoded = encode_bmp(&buffer, (3, 2), ColorType::Rgb8).unwrap();
+    let decoded = image::load_from_memory(&encoded).unwrap();
+
+    assert_eq!(decoded.to_rgb8().into_raw(), buffer);
+}
@@ -0,0 +1,59 @@
+use browser_video_capture::{HashMethod, ImageHash};
+
+const SIZE: (u32, u32) = (64, 48);
+const METHODS: [HashMethod; 3] = [
+    HashMethod::Average,
+    HashMethod::Difference,
+    HashMethod::Perceptual,
+];
+
+/// RGBA frame with the diagonal pattern, `offset` changes the brightness.
+fn frame(offset: u8, flip: bool) -> Vec<u8> {
+    (0..SIZE.0 * SIZE.1)
+        .flat_map(|index| {
+            let (x, y) = (index % SIZE.0, index / SIZE.0);
+            let x = if flip { SIZE.0 - 1 - x } else { x };
+            let value = ((x * 3 + y * 2) % 160) as u8 + offset;
+            [value, value, value, 255]
+        })
+        .collect()
+}
+
+#[test]
+fn gradient_hashes_known_bits() {
+    let gradient: Vec<u8> = (0..9 * 8).map(|index| (index % 9 * 20) as u8).collect();
+    let hash = HashMethod::Difference.hash(&gradient, (9, 8), 1);
+    assert_eq!(hash, ImageHash(u64::MAX));
+    assert_eq!(hash.to_string(), "ffffffffffffffff");
+
+    let flat = HashMethod::Average.hash(&[100; 64], (8, 8), 1);
+    assert_eq!(flat, ImageHash(0));
+}
+
+#[test]
+fn brightness_keeps_hash_close() {
+    for method in METHODS {
+        let original = method.hash(&frame(0, false), SIZE, 4);
+        let brighter = method.hash(&frame(40, false), SIZE, 4);
+        let flipped = method.hash(&frame(0, true), SIZE, 4);
+
+        assert!(original.is_similar(&brighter, 4), "{:?}", method);
+        assert!(original.distance(&flipped) > 10, "{:?}", method);
+    }
+}
+
+#[test]
+fn unique_and_nearest_hashes() {
+    let hashes = [
+        ImageHash(0b0000),
+        ImageHash(0b0001),
+        ImageHash(0xff00),
+        ImageHash(0xff01),
+        ImageHash(0x0f0f),
+    ];
+    assert_eq!(ImageHash::unique(&hashes, 1), [0, 2, 4]);
+    assert_eq!(ImageHash::unique(&hashes, 0), [0, 1, 2, 3, 4]);
+
+    assert_eq!(ImageHash(0xfe00).nearest(&hashes), Some((2, 1)));
+    assert_eq!(ImageHash(0).nearest(&[]), None);
+}
@@ -0,0 +1,74 @@
+use browser_video_capture::{warp_rgba, Homography, Warp};
+
+fn assert_close((x, y): (f64, f64), (ex, ey): (f64, f64)) {
+    assert!(
+        (x - ex).abs() < 1e-9 && (y - ey).abs() < 1e-9,
+        "({}, {}) != ({}, {})",
+        x,
+        y,
+        ex,
+        ey
+    );
+}
+
+#[test]
+fn unit_square_maps_onto_quad() {
+    let quad = [(10.0, 20.0), (110.0, 30.0), (120.0, 140.0), (5.0, 100.0)];
+    let h = Homography::from_unit_square(quad).unwrap();
+
+    for (point, expected) in [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]
+        .iter()
+        .zip(quad.iter())
+    {
+        assert_close(h.map(point.0, point.1).unwrap(), *expected);
+    }
+}
+
+#[test]
+fn inverse_roundtrip() {
+    let h = Homography::from_unit_square([(0.1, 0.2), (0.9, 0.1), (0.8, 0.9), (0.2, 0.7)]).unwrap();
+    let identity = h.then(&h.inverse().unwrap());
+
+    for (x, y) in [(0.0, 0.0), (0.25, 0.75), (0.5, 0.5), (3.0, -2.0)] {
+        assert_close(identity.map(x, y).unwrap(), (x, y));
+        assert_close(Homography::IDENTITY.map(x, y).unwrap(), (x, y));
+    }
+}
+
+#[test]
+fn degenerate_points_have_no_solution() {
+    let collinear = [(0.0, 0.0), (1.0, 1.0), (2.0, 2.0), (0.0, 1.0)];
+    assert_eq!(Homography::from_unit_square(collinear), None);
+}
+
+#[test]
+fn warp_axis_aligned_quad_crops() {
+    let (sw, sh) = (4u32, 4u32);
+    let source: Vec<u8> = (0..sw * sh)
+        .flat_map(|i| {
+            let (x, y) = (i % sw, i / sw);
+            let value = if x < 2 && y < 2 { 255 } else { 0 };
+            [value, 0, 0, 255]
+        })
+        .collect();
+
+    let warp = Warp::new([(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)], 2, 2);
+    let homography = warp.homography(sw, sh).unwrap();
+
+    let mut target = vec![0; 2 * 2 * 4];
+    warp_rgba(&source, (sw, sh), &homography, &mut target, warp.size());
+
+    assert_eq!(target, [255, 0, 0, 255].repeat(4));
+}
+
+#[test]
+fn warp_outside_source_is_transparent() {
+    let source = [255u8; 2 * 2 * 4];
+    let warp = Warp::new([(2.0, 0.0), (4.0, 0.0), (4.0, 2.0), (2.0, 2.0)], 2, 2);
+    let homography = warp.homography(2, 2).unwrap();
+
+    let mut target = vec![1; 2 * 2 * 4];
+    warp_rgba(&source, (2, 2), &homography, &mut target, warp.size());
+
+    assert!(target.iter().all(|&value| value == 0));
+}
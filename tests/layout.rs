@@ -0,0 +1,60 @@
+use browser_video_capture::{ChannelOrder, FrameLayout, RowOrder};
+
+/// Frame of 3x2 pixels with every byte set to its index.
+fn frame() -> Vec<u8> {
+    (0..24).collect()
+}
+
+#[test]
+fn default_layout_is_packed() {
+    let layout = FrameLayout::default();
+    assert!(layout.is_default());
+    assert_eq!(layout.stride(3, 4), 12);
+    assert_eq!(layout.buffer_size((3, 2), 4), 24);
+
+    let mut buffer = frame();
+    layout.apply(&mut buffer, (3, 2), 4);
+    assert_eq!(buffer, frame());
+}
+
+#[test]
+fn aligned_rows_are_padded() {
+    let layout = FrameLayout::default().alignment(16);
+    assert_eq!(layout.stride(3, 4), 16);
+    assert_eq!(layout.buffer_size((3, 2), 4), 32);
+
+    let mut buffer = frame();
+    buffer.resize(32, 0);
+    layout.apply(&mut buffer, (3, 2), 4);
+    assert_eq!(buffer[..12], frame()[..12]);
+    assert_eq!(buffer[16..28], frame()[12..]);
+
+    let mut packed = vec![0; 24];
+    layout.pack(&buffer, (3, 2), 4, &mut packed);
+    assert_eq!(packed, frame());
+}
+
+#[test]
+fn bottom_up_and_channel_orders_round_trip() {
+    for channel_order in [ChannelOrder::Rgba, ChannelOrder::Bgra, ChannelOrder::Argb].iter() {
+        let layout = FrameLayout::default()
+            .alignment(64)
+            .row_order(RowOrder::BottomUp)
+            .channel_order(*channel_order);
+
+        let mut buffer = frame();
+        buffer.resize(layout.buffer_size((3, 2), 4), 0);
+        layout.apply(&mut buffer, (3, 2), 4);
+
+        let first = &buffer[..4];
+        match channel_order {
+            ChannelOrder::Rgba => assert_eq!(first, [12, 13, 14, 15]),
+            ChannelOrder::Bgra => assert_eq!(first, [14, 13, 12, 15]),
+            ChannelOrder::Argb => assert_eq!(first, [15, 12, 13, 14]),
+        }
+
+        let mut packed = vec![0; 24];
+        layout.pack(&buffer, (3, 2), 4, &mut packed);
+        assert_eq!(packed, frame());
+    }
+}
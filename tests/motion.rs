@@ -0,0 +1,79 @@
+use browser_video_capture::{MotionDetector, MotionOptions, Rect};
+
+const SIZE: (u32, u32) = (8, 4);
+
+fn gray(value: u8) -> Vec<u8> {
+    vec![value; (SIZE.0 * SIZE.1) as usize]
+}
+
+#[test]
+fn first_frame_has_no_motion() {
+    let mut detector = MotionDetector::new(MotionOptions::default().grid(4, 2));
+    let motion = detector.update(&gray(0), SIZE, 1);
+
+    assert!(!motion.is_moving());
+    assert_eq!(motion.ratio, 0.0);
+    assert_eq!(motion.cells.len(), 8);
+
+    let motion = detector.update(&gray(10), SIZE, 1);
+    assert!(!motion.is_moving());
+}
+
+#[test]
+fn changed_block_gives_region() {
+    let mut detector = MotionDetector::new(MotionOptions::default().grid(4, 2).threshold(16));
+    detector.update(&gray(0), SIZE, 1);
+
+    // change the 4x2 block at the top-right corner
+    let mut frame = gray(0);
+    for y in 0..2 {
+        for x in 4..8 {
+            frame[y * 8 + x] = 200;
+        }
+    }
+    let motion = detector.update(&frame, SIZE, 1);
+
+    assert_eq!(motion.ratio, 0.25);
+    assert_eq!(motion.cells[2..4], [1.0, 1.0]);
+    assert_eq!(motion.regions, [Rect::new(4, 0, 4, 2)]);
+    assert_eq!(detector.mask()[4..8], [255; 4]);
+    assert_eq!(detector.mask()[..4], [0; 4]);
+
+    // the same frame again does not move
+    assert!(!detector.update(&frame, SIZE, 1).is_moving());
+}
+
+#[test]
+fn separate_regions_and_cell_sensitivity() {
+    let mut detector = MotionDetector::new(MotionOptions::default().grid(4, 2));
+    detector.update(&gray(0), SIZE, 1);
+
+    let mut frame = gray(0);
+    frame[0] = 255;
+    frame[31] = 255;
+    let motion = detector.update(&frame, SIZE, 1);
+    assert_eq!(
+        motion.regions,
+        [Rect::new(0, 0, 2, 2), Rect::new(6, 2, 2, 2)]
+    );
+
+    // ignore the top-left cell
+    detector.set_cell_sensitivity(0, 0, 2.0);
+    let motion = detector.update(&gray(0), SIZE, 1);
+    assert_eq!(motion.regions, [Rect::new(6, 2, 2, 2)]);
+}
+
+#[test]
+fn empty_grid_is_clamped() {
+    let options = MotionOptions {
+        grid: (0, 0),
+        ..Default::default()
+    };
+    let mut detector = MotionDetector::new(options);
+    assert_eq!(detector.options().grid, (1, 1));
+
+    detector.update(&gray(0), SIZE, 1);
+    let motion = detector.update(&gray(255), SIZE, 1);
+    assert_eq!(motion.cells, [1.0]);
+    assert_eq!(motion.regions, [Rect::new(0, 0, SIZE.0, SIZE.1)]);
+}
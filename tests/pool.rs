@@ -0,0 +1,43 @@
+use browser_video_capture::BufferPool;
+
+#[test]
+fn pool_recycles_dropped_buffers() {
+    let pool = BufferPool::new(2);
+    assert_eq!(pool.available(), 0);
+
+    let mut buffer = pool.take(16);
+    assert_eq!(buffer.len(), 16);
+    buffer[0] = 42;
+    let address = buffer.as_ptr();
+    drop(buffer);
+    assert_eq!(pool.available(), 1);
+
+    let buffer = pool.take(8);
+    assert_eq!(buffer.len(), 8);
+    assert_eq!(buffer.as_ptr(), address);
+    assert_eq!(pool.available(), 0);
+}
+
+#[test]
+fn pool_keeps_at_most_limit_buffers() {
+    let pool = BufferPool::new(2);
+    let buffers: Vec<_> = (0..4).map(|_| pool.take(4)).collect();
+    drop(buffers);
+    assert_eq!(pool.available(), 2);
+
+    pool.clear();
+    assert_eq!(pool.available(), 0);
+}
+
+#[test]
+fn detached_buffer_does_not_return() {
+    let pool = BufferPool::default();
+    let data = pool.take(3).into_vec();
+    assert_eq!(data, [0, 0, 0]);
+    assert_eq!(pool.available(), 0);
+
+    // buffers outliving the pool are simply dropped
+    let buffer = pool.take(3);
+    drop(pool);
+    assert_eq!(buffer.len(), 3);
+}
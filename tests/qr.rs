@@ -0,0 +1,387 @@
+#![cfg(feature = "qr")]
+
+use browser_video_capture::{CaptureMode, QrCode, QrEcLevel, QrScanner};
+
+const SCALE: usize = 4;
+const QUIET: usize = 4;
+
+/// Remainder of the BCH code of `data` with the generator of `degree`.
+fn bch(data: u32, generator: u32, degree: u32) -> u32 {
+    let mut remainder = data;
+    for _ in 0..degree {
+        remainder = (remainder << 1) ^ ((remainder >> (degree - 1)) * generator);
+    }
+    remainder & ((1 << degree) - 1)
+}
+
+/// Append the Reed-Solomon codewords to the data with the polynomial `0x11D`.
+fn reed_solomon(data: &[u8], ec: usize) -> Vec<u8> {
+    let mul = |mut a: u8, mut b: u8| {
+        let mut product = 0;
+        while b != 0 {
+            if b & 1 != 0 {
+                product ^= a;
+            }
+            a = (a << 1) ^ if a & 0x80 != 0 { 0x1D } else { 0 };
+            b >>= 1;
+        }
+        product
+    };
+
+    // generator with the roots from 1, the highest coefficient is implicit
+    let mut generator = vec![0u8; ec];
+    generator[ec - 1] = 1;
+    let mut root = 1u8;
+    for _ in 0..ec {
+        for j in 0..ec {
+            generator[j] = mul(generator[j], root) ^ generator.get(j + 1).copied().unwrap_or(0);
+        }
+        root = mul(root, 2);
+    }
+
+    let mut remainder = vec![0u8; ec];
+    for &byte in data {
+        let factor = byte ^ remainder.remove(0);
+        remainder.push(0);
+        for (r, &g) in remainder.iter_mut().zip(&generator) {
+            *r ^= mul(g, factor);
+        }
+    }
+    [data, &remainder].concat()
+}
+
+/// Append the `count` low bits of `value` from the highest one.
+fn push(bits: &mut Vec<bool>, value: u32, count: u32) {
+    (0..count)
+        .rev()
+        .for_each(|i| bits.push(value >> i & 1 != 0));
+}
+
+/// Pack the bits with the terminator into the data codewords padded to `capacity`.
+fn pack(mut bits: Vec<bool>, capacity: usize) -> Vec<u8> {
+    push(&mut bits, 0, 4);
+    let mut data: Vec<u8> = bits
+        .chunks(8)
+        .map(|byte| byte.iter().fold(0, |value, &bit| value << 1 | bit as u8) << (8 - byte.len()))
+        .collect();
+    let padding = [0xEC, 0x11];
+    (0..capacity - data.len()).for_each(|i| data.push(padding[i % 2]));
+    data
+}
+
+/// Split the data into the blocks of the `lengths`, append `ec` codewords to each
+/// and interleave them column by column.
+fn interleave(data: &[u8], lengths: &[usize], ec: usize) -> Vec<u8> {
+    let mut rest = data;
+    let blocks: Vec<Vec<u8>> = lengths
+        .iter()
+        .map(|&length| {
+            let (block, tail) = rest.split_at(length);
+            rest = tail;
+            reed_solomon(block, ec)
+        })
+        .collect();
+
+    let longest = lengths.iter().max().unwrap();
+    let mut codewords = Vec::new();
+    for i in 0..longest + ec {
+        for (block, &length) in blocks.iter().zip(lengths) {
+            // the error correction columns start after the longest data
+            let index = if i < *longest {
+                i
+            } else {
+                i - longest + length
+            };
+            if i < length || i >= *longest {
+                codewords.push(block[index]);
+            }
+        }
+    }
+    codewords
+}
+
+/// Render the symbol of the version up to `7` with the interleaved codewords.
+fn render(version: usize, level: u32, mask: u32, codewords: &[u8]) -> Vec<Vec<bool>> {
+    let size = 17 + 4 * version;
+    let mut modules = vec![vec![false; size]; size];
+    let mut function = vec![vec![false; size]; size];
+    let mut set = |modules: &mut Vec<Vec<bool>>, x: usize, y: usize, dark: bool| {
+        modules[y][x] = dark;
+        function[y][x] = true;
+    };
+
+    for i in 0..size {
+        set(&mut modules, 6, i, i % 2 == 0);
+        set(&mut modules, i, 6, i % 2 == 0);
+    }
+    for (cx, cy) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+        for dy in -4i64..=4 {
+            for dx in -4i64..=4 {
+                let (x, y) = (cx as i64 + dx, cy as i64 + dy);
+                if (0..size as i64).contains(&x) && (0..size as i64).contains(&y) {
+                    let distance = dx.abs().max(dy.abs());
+                    set(
+                        &mut modules,
+                        x as usize,
+                        y as usize,
+                        distance != 2 && distance != 4,
+                    );
+                }
+            }
+        }
+    }
+    let centers: &[i64] = match version {
+        1 => &[],
+        2..=6 => &[6, size as i64 - 7],
+        7 => &[6, 22, 38],
+        _ => unimplemented!(),
+    };
+    for &cy in centers {
+        for &cx in centers {
+            // the alignment patterns are skipped under the finders
+            let near = |c: i64| c == 6;
+            let far = |c: i64| c == size as i64 - 7;
+            if near(cx) && (near(cy) || far(cy)) || far(cx) && near(cy) {
+                continue;
+            }
+            for dy in -2i64..=2 {
+                for dx in -2i64..=2 {
+                    let (x, y) = ((cx + dx) as usize, (cy + dy) as usize);
+                    set(&mut modules, x, y, dx.abs().max(dy.abs()) != 1);
+                }
+            }
+        }
+    }
+    if version >= 7 {
+        let bits = (version as u32) << 12 | bch(version as u32, 0x1F25, 12);
+        for i in 0..18 {
+            let (a, b) = (size - 11 + i % 3, i / 3);
+            set(&mut modules, a, b, bits >> i & 1 != 0);
+            set(&mut modules, b, a, bits >> i & 1 != 0);
+        }
+    }
+
+    let data = level << 3 | mask;
+    let bits = (data << 10 | bch(data, 0x537, 10)) ^ 0x5412;
+    let bit = |i: usize| bits >> i & 1 != 0;
+    for i in 0..15 {
+        let first = match i {
+            0..=5 => (8, i),
+            6 => (8, 7),
+            7 => (8, 8),
+            8 => (7, 8),
+            _ => (14 - i, 8),
+        };
+        let second = if i < 8 {
+            (size - 1 - i, 8)
+        } else {
+            (8, size - 15 + i)
+        };
+        set(&mut modules, first.0, first.1, bit(i));
+        set(&mut modules, second.0, second.1, bit(i));
+    }
+    set(&mut modules, 8, size - 8, true);
+
+    let masked = |x: usize, y: usize| match mask {
+        0 => (x + y).is_multiple_of(2),
+        2 => x.is_multiple_of(3),
+        _ => unimplemented!(),
+    };
+    let mut i = 0;
+    let mut right = size - 1;
+    while right >= 1 {
+        if right == 6 {
+            right = 5;
+        }
+        for vertical in 0..size {
+            for j in 0..2 {
+                let x = right - j;
+                let upward = (right + 1) & 2 == 0;
+                let y = if upward {
+                    size - 1 - vertical
+                } else {
+                    vertical
+                };
+                if !function[y][x] {
+                    let bit =
+                        i < codewords.len() * 8 && codewords[i >> 3] >> (7 - (i & 7)) & 1 != 0;
+                    modules[y][x] = bit ^ masked(x, y);
+                    i += 1;
+                }
+            }
+        }
+        if right < 2 {
+            break;
+        }
+        right -= 2;
+    }
+    modules
+}
+
+/// Draw the symbol with the quiet zone as the grayscale frame.
+fn image(modules: &[Vec<bool>]) -> (Vec<u8>, (u32, u32)) {
+    let side = (modules.len() + 2 * QUIET) * SCALE;
+    let pixels = (0..side * side)
+        .map(|index| {
+            let (x, y) = (index % side / SCALE, index / side / SCALE);
+            let inside = (QUIET..QUIET + modules.len()).contains(&x)
+                && (QUIET..QUIET + modules.len()).contains(&y);
+            if inside && modules[y - QUIET][x - QUIET] {
+                30
+            } else {
+                220
+            }
+        })
+        .collect();
+    (pixels, (side as u32, side as u32))
+}
+
+fn assert_near((x, y): (f64, f64), (ex, ey): (f64, f64)) {
+    assert!(
+        (x - ex).abs() < 2.0 && (y - ey).abs() < 2.0,
+        "{:?} {:?}",
+        (x, y),
+        (ex, ey)
+    );
+}
+
+#[test]
+fn known_symbol_decodes_with_corners() {
+    let codewords = [
+        32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17, 196, 35, 39, 119,
+        235, 215, 231, 226, 93, 23,
+    ];
+    let (frame, size) = image(&render(1, 0, 0, &codewords));
+    let codes = QrScanner::default().scan(&frame, size, 1);
+
+    assert_eq!(codes.len(), 1);
+    let code = &codes[0];
+    assert_eq!(code.text(), "HELLO WORLD");
+    assert_eq!((code.version, code.ec_level), (1, QrEcLevel::Medium));
+
+    let (start, end) = ((QUIET * SCALE) as f64, ((QUIET + 21) * SCALE) as f64);
+    let expected = [(start, start), (end, start), (end, end), (start, end)];
+    for (corner, expected) in code.corners.iter().zip(expected) {
+        assert_near(*corner, expected);
+    }
+}
+
+#[test]
+fn damaged_rotated_symbol_decodes() {
+    let text = b"https://example.com/frame";
+    let mut bits = Vec::new();
+    push(&mut bits, 4, 4);
+    push(&mut bits, text.len() as u32, 8);
+    text.iter()
+        .for_each(|&byte| push(&mut bits, byte as u32, 8));
+
+    // version 2 with the low level has one block of 34 data codewords
+    let mut modules = render(2, 1, 2, &reed_solomon(&pack(bits, 34), 10));
+    for (x, y) in [(24, 24), (15, 20), (12, 14)] {
+        modules[y][x] = !modules[y][x];
+    }
+    let size = modules.len();
+    let rotated: Vec<Vec<bool>> = (0..size)
+        .map(|y| (0..size).map(|x| modules[size - 1 - x][y]).collect())
+        .collect();
+
+    let (frame, frame_size) = image(&rotated);
+    let rgba: Vec<u8> = frame.iter().flat_map(|&l| [l, l, l, 255]).collect();
+    let codes = QrScanner::default().scan(&rgba, frame_size, 4);
+
+    assert_eq!(codes.len(), 1);
+    assert_eq!(codes[0].payload, text);
+    assert_eq!((codes[0].version, codes[0].ec_level), (2, QrEcLevel::Low));
+
+    // the top-left corner of the symbol is at the top-right of the frame
+    let (start, end) = ((QUIET * SCALE) as f64, ((QUIET + size) * SCALE) as f64);
+    assert_near(codes[0].corners[0], (end, start));
+    assert_near(codes[0].corners[2], (start, end));
+}
+
+/// Byte segments of the payload, preceded by the ECI designator if any.
+fn byte_segments(payload: &[u8], eci: Option<&[u8]>) -> Vec<bool> {
+    let mut bits = Vec::new();
+    if let Some(designator) = eci {
+        push(&mut bits, 7, 4);
+        designator
+            .iter()
+            .for_each(|&byte| push(&mut bits, byte as u32, 8));
+    }
+    push(&mut bits, 4, 4);
+    push(&mut bits, payload.len() as u32, 8);
+    payload
+        .iter()
+        .for_each(|&byte| push(&mut bits, byte as u32, 8));
+    bits
+}
+
+fn scan(modules: &[Vec<bool>]) -> QrCode {
+    let (frame, size) = image(modules);
+    let mut codes = QrScanner::default().scan(&frame, size, 1);
+    assert_eq!(codes.len(), 1);
+    codes.remove(0)
+}
+
+#[test]
+fn eci_segments_are_skipped() {
+    // UTF-8 in one byte and the designator `1000` in two bytes
+    for designator in [&[26][..], &[0x83, 0xE8]] {
+        let bits = byte_segments("grüße".as_bytes(), Some(designator));
+        // version 1 with the low level has one block of 19 data codewords
+        let code = scan(&render(1, 1, 0, &reed_solomon(&pack(bits, 19), 7)));
+
+        assert_eq!(code.text(), "grüße");
+        assert_eq!((code.version, code.ec_level), (1, QrEcLevel::Low));
+    }
+}
+
+#[test]
+fn short_and_long_blocks_decode() {
+    let text = b"two short blocks of 15 and two long of 16 codewords";
+    let data = pack(byte_segments(text, None), 62);
+    // version 5 with the quartile level has the blocks of 18 correction codewords
+    let code = scan(&render(5, 3, 2, &interleave(&data, &[15, 15, 16, 16], 18)));
+
+    assert_eq!(code.payload, text);
+    assert_eq!((code.version, code.ec_level), (5, QrEcLevel::Quartile));
+}
+
+#[test]
+fn version_information_is_read() {
+    let text: Vec<u8> = (0..150).map(|i| b'a' + i % 26).collect();
+    let data = pack(byte_segments(&text, None), 156);
+    // version 7 with the low level has two blocks of 78 data codewords
+    let mut modules = render(7, 1, 0, &interleave(&data, &[78, 78], 20));
+    // a damaged version block is recovered from the other one
+    let size = modules.len();
+    modules[0][size - 11] ^= true;
+    let code = scan(&modules);
+
+    assert_eq!(code.payload, text);
+    assert_eq!((code.version, code.ec_level), (7, QrEcLevel::Low));
+}
+
+#[test]
+fn corners_map_back_to_source() {
+    assert_eq!(
+        CaptureMode::Fill.map_to_source((32.0, 24.0), (64, 48), (640, 480)),
+        (320.0, 240.0)
+    );
+    assert_eq!(
+        CaptureMode::Put(10, -5).map_to_source((20.0, 20.0), (64, 48), (640, 480)),
+        (10.0, 25.0)
+    );
+    assert_eq!(
+        CaptureMode::Pinhole.map_to_source((50.0, 50.0), (100, 100), (200, 100)),
+        (100.0, 50.0)
+    );
+    assert!(QrScanner::default().scan(&[255; 64], (8, 8), 1).is_empty());
+}
+
+#[test]
+fn invalid_frames_are_ignored() {
+    let scanner = QrScanner::default();
+    assert!(scanner.scan(&[0; 64], (8, 8), 0).is_empty());
+    assert!(scanner.scan(&[0; 64], (u32::MAX, u32::MAX), 4).is_empty());
+}
@@ -0,0 +1,91 @@
+use browser_video_capture::{warp_rgba_with, Homography, Resample};
+
+const FILTERS: [Resample; 5] = [
+    Resample::Nearest,
+    Resample::Bilinear,
+    Resample::Area,
+    Resample::Bicubic,
+    Resample::Lanczos,
+];
+
+fn gray(width: u32, height: u32, value: impl Fn(u32, u32) -> u8) -> Vec<u8> {
+    (0..width * height)
+        .flat_map(|i| {
+            let v = value(i % width, i / width);
+            [v, v, v, 255]
+        })
+        .collect()
+}
+
+fn scale(
+    source: &[u8],
+    source_size: (u32, u32),
+    target_size: (u32, u32),
+    resample: Resample,
+) -> Vec<u8> {
+    let mut target = vec![0; (target_size.0 * target_size.1 * 4) as usize];
+    warp_rgba_with(
+        source,
+        source_size,
+        &Homography::IDENTITY,
+        &mut target,
+        target_size,
+        resample,
+    );
+    target
+}
+
+#[test]
+fn constant_frame_stays_constant() {
+    let source = gray(8, 8, |_, _| 100);
+
+    for resample in FILTERS {
+        for size in [(3, 3), (8, 8), (13, 5)] {
+            let target = scale(&source, (8, 8), size, resample);
+            assert!(
+                target.chunks(4).all(|pixel| pixel == [100, 100, 100, 255]),
+                "{:?} {:?}",
+                resample,
+                size
+            );
+        }
+    }
+}
+
+#[test]
+fn same_size_is_exact_copy() {
+    let source = gray(5, 4, |x, y| (x * 40 + y * 7) as u8);
+
+    for resample in FILTERS {
+        assert_eq!(
+            scale(&source, (5, 4), (5, 4), resample),
+            source,
+            "{:?}",
+            resample
+        );
+    }
+}
+
+#[test]
+fn area_averages_checkerboard() {
+    let source = gray(8, 8, |x, y| if (x + y) % 2 == 0 { 0 } else { 255 });
+
+    let nearest = scale(&source, (8, 8), (2, 2), Resample::Nearest);
+    assert!(nearest
+        .chunks(4)
+        .all(|pixel| pixel[0] == 0 || pixel[0] == 255));
+
+    let area = scale(&source, (8, 8), (2, 2), Resample::Area);
+    assert!(area.chunks(4).all(|pixel| (127..=128).contains(&pixel[0])));
+}
+
+#[test]
+fn bilinear_interpolates_upscaled_edge() {
+    let source = gray(2, 1, |x, _| if x == 0 { 0 } else { 200 });
+    let target = scale(&source, (2, 1), (4, 1), Resample::Bilinear);
+
+    assert_eq!(
+        target.chunks(4).map(|pixel| pixel[0]).collect::<Vec<_>>(),
+        [0, 50, 150, 200]
+    );
+}
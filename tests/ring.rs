@@ -0,0 +1,86 @@
+use browser_video_capture::{FrameMeta, FrameRing};
+
+fn meta(timestamp: f64) -> FrameMeta {
+    FrameMeta {
+        timestamp,
+        width: 2,
+        height: 1,
+        channels: 1,
+    }
+}
+
+fn timestamps(ring: &FrameRing) -> Vec<f64> {
+    ring.iter().map(|frame| frame.meta.timestamp).collect()
+}
+
+#[test]
+fn ring_overwrites_oldest_frames() {
+    let mut ring = FrameRing::new(3, 2);
+    assert!(ring.is_empty());
+    assert!(ring.latest().is_none());
+
+    for i in 0..5u8 {
+        assert!(ring.push(&[i, i + 10], meta(i as f64)));
+    }
+
+    assert_eq!(ring.len(), 3);
+    assert_eq!(timestamps(&ring), [2.0, 3.0, 4.0]);
+    assert_eq!(ring.get(0).unwrap().data, &[2, 12]);
+    assert_eq!(ring.latest().unwrap().data, &[4, 14]);
+    assert!(ring.get(3).is_none());
+}
+
+#[test]
+fn ring_rejects_frames_larger_than_slot() {
+    let mut ring = FrameRing::new(2, 2);
+    let large = FrameMeta {
+        width: 3,
+        ..meta(0.0)
+    };
+
+    assert!(!ring.push(&[0; 3], large));
+    assert!(!ring.push(&[0; 3], meta(0.0)));
+    assert!(ring.is_empty());
+
+    // smaller frames use the part of the slot
+    let small = FrameMeta {
+        width: 1,
+        ..meta(1.0)
+    };
+    assert!(ring.push(&[7], small));
+    assert_eq!(ring.latest().unwrap().data, &[7]);
+}
+
+#[test]
+fn dump_last_seconds() {
+    let mut ring = FrameRing::new(10, 2);
+    for i in 0..10 {
+        ring.push(&[i, 0], meta(i as f64 * 0.5));
+    }
+
+    let dump = ring.dump(1.0);
+    let times: Vec<f64> = dump.iter().map(|(meta, _)| meta.timestamp).collect();
+    assert_eq!(times, [3.5, 4.0, 4.5]);
+    assert_eq!(dump[0].1, [7, 0]);
+}
+
+#[test]
+fn incident_collects_frames_before_and_after_event() {
+    let mut ring = FrameRing::new(8, 2);
+    for i in 0..6 {
+        ring.push(&[i, 0], meta(i as f64));
+    }
+
+    ring.trigger(5.0, 2.0, 2.0);
+    assert!(ring.is_triggered());
+    assert!(ring.poll_incident().is_none());
+
+    ring.push(&[6, 0], meta(6.0));
+    assert!(ring.poll_incident().is_none());
+    ring.push(&[7, 0], meta(7.0));
+
+    let incident = ring.poll_incident().unwrap();
+    let times: Vec<f64> = incident.iter().map(|(meta, _)| meta.timestamp).collect();
+    assert_eq!(times, [3.0, 4.0, 5.0, 6.0, 7.0]);
+    assert!(!ring.is_triggered());
+}
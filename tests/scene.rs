@@ -0,0 +1,64 @@
+use browser_video_capture::{FrameStats, SceneDetector, SceneOptions};
+
+/// Stats of the gray frame with `bright` pixels of the value `200` out of `16`.
+fn stats(bright: usize) -> FrameStats {
+    let frame: Vec<u8> = (0..16).map(|i| if i < bright { 200 } else { 20 }).collect();
+    FrameStats::from_frame(&frame, 1)
+}
+
+#[test]
+fn histogram_distance_of_frames() {
+    assert_eq!(stats(0).distance(&stats(0)), 0.0);
+    assert_eq!(stats(0).distance(&stats(16)), 1.0);
+    assert_eq!(stats(4).distance(&stats(8)), 0.25);
+}
+
+#[test]
+fn cut_starts_new_scene() {
+    let mut detector = SceneDetector::new(SceneOptions::default().min_interval(1.0));
+
+    let first = detector.update(stats(0), 0.0).unwrap();
+    assert_eq!((first.timestamp, first.distance), (0.0, 1.0));
+    assert_eq!(detector.update(stats(0), 1.0), None);
+    assert_eq!(detector.update(stats(1), 2.0), None);
+
+    let cut = detector.update(stats(12), 3.0).unwrap();
+    assert_eq!((cut.timestamp, cut.distance), (3.0, 0.6875));
+
+    // the cut right after the previous one is ignored
+    assert_eq!(detector.update(stats(0), 3.5), None);
+    assert!(detector.update(stats(16), 4.5).is_some());
+}
+
+#[test]
+fn threshold_adapts_to_recent_distances() {
+    let options = SceneOptions::default()
+        .window(4)
+        .factor(2.0)
+        .min_threshold(0.4);
+    let mut detector = SceneDetector::new(options);
+    assert_eq!(detector.threshold(), 0.4);
+
+    // busy scene with distances alternating between 0.125 and 0.375
+    detector.update(stats(0), 0.0);
+    for (index, bright) in [2, 8, 10, 4].iter().enumerate() {
+        assert_eq!(detector.update(stats(*bright), index as f64 + 1.0), None);
+    }
+    assert_eq!(detector.threshold(), 0.5);
+
+    assert_eq!(detector.update(stats(11), 10.0), None);
+    assert!(detector.update(stats(0), 11.0).is_some());
+}
+
+#[test]
+fn empty_window_keeps_last_distance() {
+    let mut options = SceneOptions::default().factor(2.0).min_threshold(0.4);
+    options.window = 0;
+    let mut detector = SceneDetector::new(options);
+
+    detector.update(stats(0), 0.0);
+    for (index, bright) in [2, 8, 10, 4].iter().enumerate() {
+        assert_eq!(detector.update(stats(*bright), index as f64 + 1.0), None);
+    }
+    assert_eq!(detector.threshold(), 0.4);
+}
@@ -0,0 +1,73 @@
+use browser_video_capture::{FrameMeta, FrameRing, Rect, SharpestFrame, SharpnessMethod};
+
+const SIZE: (u32, u32) = (8, 4);
+
+/// Gray frame with the vertical edge between the columns `3` and `4`
+/// spread over `ramp` pixels.
+fn edge(ramp: u32) -> Vec<u8> {
+    (0..SIZE.0 * SIZE.1)
+        .map(|index| {
+            let x = (index % SIZE.0) as f64 + 0.5;
+            let t = ((x - 4.0) / ramp as f64 + 0.5).clamp(0.0, 1.0);
+            (t * 200.0).round() as u8
+        })
+        .collect()
+}
+
+#[test]
+fn sharp_edge_scores_above_blurred_one() {
+    for method in [SharpnessMethod::Laplacian, SharpnessMethod::Tenengrad] {
+        assert_eq!(method.score(&[80; 32], SIZE, 1, None), 0.0);
+
+        let sharp = method.score(&edge(1), SIZE, 1, None);
+        let blurred = method.score(&edge(6), SIZE, 1, None);
+        assert!(sharp > blurred, "{:?}: {} <= {}", method, sharp, blurred);
+    }
+}
+
+#[test]
+fn rect_limits_scored_area() {
+    let frame = edge(1);
+    let method = SharpnessMethod::Tenengrad;
+
+    assert_eq!(
+        method.score(&frame, SIZE, 1, Some(Rect::new(0, 0, 2, 4))),
+        0.0
+    );
+    assert!(method.score(&frame, SIZE, 1, Some(Rect::new(2, 0, 4, 4))) > 0.0);
+    assert_eq!(
+        method.score(&frame, SIZE, 1, Some(Rect::new(8, 0, 4, 4))),
+        0.0
+    );
+}
+
+#[test]
+fn sharpest_frame_in_window() {
+    let mut selector = SharpestFrame::new(2);
+    assert!(selector.push(0.0, 5.0));
+    assert!(!selector.push(1.0, 3.0));
+    assert_eq!(selector.best(), Some((0.0, 5.0)));
+
+    // the sharpest frame moves out of the window
+    assert!(!selector.push(2.0, 1.0));
+    assert_eq!(selector.best(), Some((1.0, 3.0)));
+
+    let mut ring = FrameRing::new(3, 32);
+    for (timestamp, ramp) in [(0.0, 1), (1.0, 6), (2.0, 3)] {
+        let meta = FrameMeta {
+            timestamp,
+            width: SIZE.0,
+            height: SIZE.1,
+            channels: 1,
+        };
+        assert!(ring.push(&edge(ramp), meta));
+    }
+
+    let sharpest = |count| ring.sharpest(count, SharpnessMethod::Laplacian);
+    assert_eq!(sharpest(3).map(|frame| frame.meta.timestamp), Some(0.0));
+    assert_eq!(sharpest(2).map(|frame| frame.meta.timestamp), Some(2.0));
+    assert_eq!(
+        selector.find(&ring).map(|frame| frame.meta.timestamp),
+        Some(1.0)
+    );
+}
@@ -0,0 +1,63 @@
+use browser_video_capture::{seek_interval, FrameMeta, Rect, SpriteSheet};
+
+fn frame(timestamp: f64, size: (u32, u32), value: u8) -> (FrameMeta, Vec<u8>) {
+    let meta = FrameMeta {
+        timestamp,
+        width: size.0,
+        height: size.1,
+        channels: 4,
+    };
+    (meta, vec![value; meta.buffer_size()])
+}
+
+#[test]
+fn interval_stops_before_end() {
+    assert_eq!(seek_interval(0.0, 5.0, 12.0), [0.0, 5.0, 10.0]);
+    assert_eq!(seek_interval(1.0, 5.0, 11.0), [1.0, 6.0]);
+    assert!(seek_interval(0.0, 0.0, 10.0).is_empty());
+    assert!(seek_interval(0.0, 1.0, f64::INFINITY).is_empty());
+}
+
+#[test]
+fn frames_fill_tiles_row_by_row() {
+    let frames = [
+        frame(0.0, (2, 1), 10),
+        frame(1.0, (4, 2), 20),
+        frame(2.0, (2, 1), 30),
+    ];
+    let sheet = SpriteSheet::from_frames(&frames, 2, (2, 1));
+    assert_eq!(sheet.size(), (4, 2));
+    assert!(sheet.is_full());
+    assert_eq!(sheet.mosaic().tile(2), Some(Rect::new(0, 1, 2, 1)));
+
+    let pixels: Vec<u8> = sheet.data().chunks_exact(4).map(|pixel| pixel[0]).collect();
+    assert_eq!(pixels, [10, 10, 20, 20, 30, 30, 0, 0]);
+
+    let mut sheet = sheet;
+    assert!(!sheet.push(&frames[0].0, &frames[0].1));
+}
+
+#[test]
+fn short_frame_is_rejected() {
+    let (meta, data) = frame(0.0, (2, 2), 10);
+    let mut sheet = SpriteSheet::new(1, 1, (2, 2));
+    assert!(!sheet.push(&meta, &data[..8]));
+    assert!(!sheet.push(&FrameMeta { channels: 0, ..meta }, &data));
+    assert!(sheet.is_empty());
+    assert!(sheet.push(&meta, &data));
+}
+
+#[test]
+fn vtt_indexes_tiles() {
+    let frames = [frame(0.0, (4, 2), 0), frame(65.5, (4, 2), 0)];
+    let sheet = SpriteSheet::from_frames(&frames, 4, (4, 2));
+    assert_eq!(sheet.size(), (8, 2));
+
+    let vtt = sheet.vtt("sprite.png", 3725.0);
+    assert_eq!(
+        vtt,
+        "WEBVTT\n\
+         \n00:00:00.000 --> 00:01:05.500\nsprite.png#xywh=0,0,4,2\n\
+         \n00:01:05.500 --> 01:02:05.000\nsprite.png#xywh=4,0,4,2\n"
+    );
+}
@@ -0,0 +1,48 @@
+use browser_video_capture::{FrameStats, StatsChannel};
+
+#[test]
+fn stats_of_rgba_frame() {
+    let frame = [
+        255, 0, 0, 255, //
+        0, 255, 0, 255, //
+        0, 0, 255, 255, //
+        255, 255, 255, 128,
+    ];
+    let stats = FrameStats::from_frame(&frame, 4);
+
+    assert_eq!(stats.count(), 4);
+    assert_eq!(stats.histogram(StatsChannel::Red)[255], 2);
+    assert_eq!(stats.histogram(StatsChannel::Red)[0], 2);
+    assert_eq!(stats.mean(StatsChannel::Green), 127.5);
+    assert_eq!(stats.min(StatsChannel::Alpha), Some(128));
+    assert_eq!(stats.max(StatsChannel::Alpha), Some(255));
+
+    // BT.601 luminance of the pure colors and white
+    assert_eq!(stats.min(StatsChannel::Luma), Some(29));
+    assert_eq!(stats.max(StatsChannel::Luma), Some(255));
+    assert_eq!(stats.histogram(StatsChannel::Luma)[76], 1);
+    assert_eq!(stats.histogram(StatsChannel::Luma)[150], 1);
+}
+
+#[test]
+fn stats_of_gray_frame() {
+    let stats = FrameStats::from_frame(&[10, 20, 30, 40], 1);
+
+    for channel in StatsChannel::ALL.iter().take(3) {
+        assert_eq!(stats.mean(*channel), 25.0);
+    }
+    assert_eq!(stats.mean(StatsChannel::Luma), 25.0);
+    assert_eq!(stats.min(StatsChannel::Alpha), Some(255));
+    assert_eq!(stats.dark_fraction(25), 0.5);
+}
+
+#[test]
+fn stats_of_empty_frame() {
+    let stats = FrameStats::from_frame(&[], 4);
+
+    assert_eq!(stats.count(), 0);
+    assert_eq!(stats.mean(StatsChannel::Luma), 0.0);
+    assert_eq!(stats.min(StatsChannel::Luma), None);
+    assert_eq!(stats.max(StatsChannel::Red), None);
+    assert_eq!(stats.dark_fraction(128), 0.0);
+}
@@ -0,0 +1,52 @@
+use browser_video_capture::{TemporalFilter, TemporalMode};
+
+const SIZE: (u32, u32) = (1, 1);
+
+#[test]
+fn denoise_averages_still_pixels() {
+    let mut filter = TemporalFilter::new(TemporalMode::Denoise {
+        strength: 0.5,
+        motion_threshold: 0.0,
+    });
+
+    let mut frame = [100, 100, 100, 255];
+    filter.apply(&mut frame, SIZE);
+    assert_eq!(frame, [100, 100, 100, 255]);
+
+    let mut frame = [110, 90, 100, 255];
+    filter.apply(&mut frame, SIZE);
+    assert_eq!(frame, [105, 95, 100, 255]);
+    assert_eq!(filter.frames(), 2);
+}
+
+#[test]
+fn motion_takes_new_frame() {
+    let mut filter = TemporalFilter::new(TemporalMode::default());
+
+    let mut frame = [0, 0, 0, 255];
+    filter.apply(&mut frame, SIZE);
+    let mut frame = [200, 200, 200, 255];
+    filter.apply(&mut frame, SIZE);
+    assert_eq!(frame, [200, 200, 200, 255]);
+
+    // the new size restarts the history
+    let mut frame = [50; 8];
+    filter.apply(&mut frame, (2, 1));
+    assert_eq!(frame, [50; 8]);
+    assert_eq!(filter.frames(), 1);
+}
+
+#[test]
+fn stack_averages_frames() {
+    let mut filter = TemporalFilter::new(TemporalMode::Stack(3));
+
+    let frames: Vec<u8> = [30, 60, 90, 90]
+        .iter()
+        .map(|&value| {
+            let mut frame = [value; 4];
+            filter.apply(&mut frame, SIZE);
+            frame[0]
+        })
+        .collect();
+    assert_eq!(frames, [30, 45, 60, 70]);
+}
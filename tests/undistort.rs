@@ -0,0 +1,52 @@
+use browser_video_capture::{RemapTable, Resample, Undistort};
+
+#[test]
+fn distort_without_coefficients_is_identity() {
+    let undistort = Undistort::new(500.0, 500.0, 320.0, 240.0);
+
+    for (x, y) in [(0.0, 0.0), (320.0, 240.0), (639.0, 17.5)] {
+        assert_eq!(undistort.distort(x, y), (x, y));
+    }
+}
+
+#[test]
+fn distort_radial_and_tangential() {
+    let radial = Undistort::new(100.0, 100.0, 50.0, 50.0).radial(0.1, 0.0, 0.0);
+    assert_eq!(radial.distort(50.0, 50.0), (50.0, 50.0));
+
+    let (x, y) = radial.distort(150.0, 50.0);
+    assert!((x - 160.0).abs() < 1e-9 && (y - 50.0).abs() < 1e-9);
+
+    let tangential = Undistort::new(100.0, 100.0, 50.0, 50.0).tangential(0.0, 0.1);
+    let (x, y) = tangential.distort(150.0, 50.0);
+    assert!((x - 180.0).abs() < 1e-9 && (y - 50.0).abs() < 1e-9);
+}
+
+#[test]
+fn remap_table_without_distortion_copies_frame() {
+    let size = (3, 2);
+    let source: Vec<u8> = (0..3 * 2 * 4).map(|value| value as u8 * 10).collect();
+
+    let table = RemapTable::new(&Undistort::new(3.0, 2.0, 1.5, 1.0), size, size);
+    let mut target = vec![0; source.len()];
+    table.apply(&source, &mut target, Resample::Bilinear);
+
+    assert_eq!(table.source_size(), size);
+    assert_eq!(table.target_size(), size);
+    assert_eq!(target, source);
+}
+
+#[test]
+fn remap_table_clears_pixels_outside_frame() {
+    let size = (4, 4);
+    let source = vec![255; 4 * 4 * 4];
+
+    // strong pincushion pushes the corners out of the frame
+    let undistort = Undistort::new(2.0, 2.0, 2.0, 2.0).radial(1.0, 0.0, 0.0);
+    let table = RemapTable::new(&undistort, size, size);
+    let mut target = vec![1; source.len()];
+    table.apply(&source, &mut target, Resample::Bilinear);
+
+    assert_eq!(&target[..4], &[0, 0, 0, 0]);
+    assert_eq!(&target[(5 * 4)..(6 * 4)], &[255, 255, 255, 255]);
+}
@@ -26,7 +26,7 @@ use gloo::{
 };
 
 use browser_video_capture::{
-    impl_canvas_capture_area, BrowserCapture, BrowserCaptureBuilder, BrowserVideoCapture, CaptureArea, CaptureMode, GLVersion, HtmlContextOptions2D, HtmlContextOptionsGL, OffscreenContextOptions2D, OffscreenContextOptionsGL, SupportedCanvas, SupportedOptions
+    impl_canvas_capture_area, BrowserCapture, BrowserCaptureBuilder, BrowserVideoCapture, CaptureArea, CaptureMode, GLVersion, HtmlContextOptions2D, HtmlContextOptionsGL, OffscreenContextOptions2D, OffscreenContextOptionsGL, SupportedCanvas, SupportedOptions, StreamMode, buffer_frame, BufferPool, FrameLayout, RowOrder, ChannelOrder, FrameStats, StatsChannel, MotionDetector, MotionOptions, BackgroundModel, BackgroundOptions, TemporalMode, SharpnessMethod, Rect, HashMethod, SceneDetector, SceneOptions
 };
 
 const DEFAULT_WIDTH: u32 = 300;
@@ -232,6 +232,391 @@ async fn capture_simple_four_color(
     assert_eq!(data.get_pixel(r, 0), &Rgba([0, 255, 0, 255]));
     assert_eq!(data.get_pixel(0, b), &Rgba([0, 0, 255, 255]));
     assert_eq!(data.get_pixel(r, b), &Rgba([255, 255, 255, 255]));
+
+    assert_eq!(cap.sample(0, 0), [255, 0, 0, 255]);
+    assert_eq!(cap.sample(r as i32, 0), [0, 255, 0, 255]);
+    assert_eq!(cap.sample(0, b as i32), [0, 0, 255, 255]);
+    assert_eq!(cap.sample(r as i32, b as i32), [255, 255, 255, 255]);
+    assert_eq!(cap.sample(-1, 0), [0, 0, 0, 0]);
+
+    // the rectangle across the bottom-right corner of the capture area
+    let mut rect = [0; 16];
+    cap.retrieve_rect(r as i32, b as i32, 2, 2, &mut rect);
+    assert_eq!(rect[..4], [255, 255, 255, 255]);
+    assert!(rect[4..].iter().all(|&v| v == 0));
+}
+
+#[rstest]
+#[wasm::test]
+async fn stats_match_captured_frame(
+    #[values(
+        HtmlContextOptions2D::default().into(),
+        HtmlContextOptionsGL::default().into(),
+        OffscreenContextOptionsGL::default().version(GLVersion::WebGL2).into()
+    )]
+    options: SupportedOptions,
+) {
+    let setup = CaptureSetup::from_size(8, 4);
+    let cap = create_capture(8, 4, options);
+
+    setup.context.set_fill_style(&"rgb(255, 0, 0)".into());
+    setup.context.fill_rect(0.0, 0.0, 4.0, 4.0);
+    setup.context.set_fill_style(&"rgb(0, 0, 0)".into());
+    setup.context.fill_rect(4.0, 0.0, 4.0, 4.0);
+
+    wait_next_frame(&setup.video).await;
+    cap.capture(&setup.video, CaptureMode::Fill);
+
+    let stats = cap.stats();
+    assert_eq!(stats, FrameStats::from_frame(&cap.packed_data(), 4));
+    assert_eq!(stats.count(), 32);
+    assert_eq!(stats.mean(StatsChannel::Red), 127.5);
+    assert_eq!(stats.min(StatsChannel::Luma), Some(0));
+    assert_eq!(stats.max(StatsChannel::Luma), Some(76));
+}
+
+#[rstest]
+#[wasm::test]
+async fn motion_detects_changed_half(
+    #[values(
+        HtmlContextOptions2D::default().into(),
+        HtmlContextOptionsGL::default().into(),
+        OffscreenContextOptionsGL::default().version(GLVersion::WebGL2).into()
+    )]
+    options: SupportedOptions,
+) {
+    let setup = CaptureSetup::from_size(8, 4);
+    let cap = create_capture(8, 4, options);
+    let mut detector = MotionDetector::new(MotionOptions::default().grid(2, 1));
+
+    setup.context.set_fill_style(&"rgb(0, 0, 0)".into());
+    setup.context.fill_rect(0.0, 0.0, 8.0, 4.0);
+    wait_next_frame(&setup.video).await;
+    cap.capture(&setup.video, CaptureMode::Fill);
+    assert!(!detector.detect(&cap).is_moving());
+
+    setup.context.set_fill_style(&"rgb(255, 255, 255)".into());
+    setup.context.fill_rect(4.0, 0.0, 4.0, 4.0);
+    wait_next_frame(&setup.video).await;
+    cap.capture(&setup.video, CaptureMode::Fill);
+
+    let motion = detector.detect(&cap);
+    assert_eq!(motion.ratio, 0.5);
+    assert_eq!(motion.cells, [0.0, 1.0]);
+    assert_eq!(motion.regions.len(), 1);
+    assert_eq!(motion.regions[0].x, 4);
+}
+
+#[rstest]
+#[wasm::test]
+async fn background_model_finds_new_object(
+    #[values(
+        HtmlContextOptions2D::default().into(),
+        HtmlContextOptionsGL::default().version(GLVersion::WebGL2).into()
+    )]
+    options: SupportedOptions,
+) {
+    let setup = CaptureSetup::from_size(8, 4);
+    let cap = create_capture(8, 4, options);
+    let mut model = BackgroundModel::new(BackgroundOptions::default());
+
+    setup.context.set_fill_style(&"rgb(0, 0, 0)".into());
+    setup.context.fill_rect(0.0, 0.0, 8.0, 4.0);
+    wait_next_frame(&setup.video).await;
+    cap.capture(&setup.video, CaptureMode::Fill);
+    assert_eq!(model.update_capture(&cap), 0.0);
+
+    setup.context.set_fill_style(&"rgb(255, 255, 255)".into());
+    setup.context.fill_rect(0.0, 0.0, 2.0, 4.0);
+    wait_next_frame(&setup.video).await;
+    cap.capture(&setup.video, CaptureMode::Fill);
+
+    assert_eq!(model.update_capture(&cap), 0.25);
+    assert_eq!(model.mask()[..3], [255, 255, 0]);
+    // the captured frame keeps its alpha
+    assert_eq!(cap.sample(0, 0)[3], 255);
+}
+
+#[rstest]
+#[wasm::test]
+async fn temporal_stack_averages_frames(
+    #[values(
+        HtmlContextOptions2D::default().into(),
+        HtmlContextOptionsGL::default().into(),
+        HtmlContextOptionsGL::default().version(GLVersion::WebGL2).into()
+    )]
+    options: SupportedOptions,
+) {
+    let setup = CaptureSetup::from_size(4, 4);
+    let cap = create_capture(4, 4, options);
+    cap.set_temporal(Some(TemporalMode::Stack(2)));
+    assert_eq!(cap.temporal(), Some(TemporalMode::Stack(2)));
+
+    setup.context.set_fill_style(&"rgb(0, 0, 0)".into());
+    setup.context.fill_rect(0.0, 0.0, 4.0, 4.0);
+    wait_next_frame(&setup.video).await;
+    cap.capture(&setup.video, CaptureMode::Fill);
+    assert_eq!(cap.sample(1, 1)[..3], [0, 0, 0]);
+
+    setup.context.set_fill_style(&"rgb(200, 200, 200)".into());
+    setup.context.fill_rect(0.0, 0.0, 4.0, 4.0);
+    wait_next_frame(&setup.video).await;
+    cap.capture(&setup.video, CaptureMode::Fill);
+
+    let pixel = cap.sample(1, 1);
+    assert!(pixel[..3].iter().all(|&value| value.abs_diff(100) <= 1));
+}
+
+#[rstest]
+#[wasm::test]
+async fn sharpness_matches_cpu_score(
+    #[values(
+        HtmlContextOptions2D::default().into(),
+        HtmlContextOptionsGL::default().into(),
+        HtmlContextOptionsGL::default().version(GLVersion::WebGL2).into()
+    )]
+    options: SupportedOptions,
+    #[values(SharpnessMethod::Laplacian, SharpnessMethod::Tenengrad)] method: SharpnessMethod,
+) {
+    let setup = CaptureSetup::from_size(8, 4);
+    let cap = create_capture(8, 4, options);
+
+    setup.context.set_fill_style(&"rgb(0, 0, 0)".into());
+    setup.context.fill_rect(0.0, 0.0, 8.0, 4.0);
+    setup.context.set_fill_style(&"rgb(200, 200, 200)".into());
+    setup.context.fill_rect(4.0, 0.0, 4.0, 4.0);
+    wait_next_frame(&setup.video).await;
+    cap.capture(&setup.video, CaptureMode::Fill);
+
+    let frame = cap.packed_data();
+    let size = cap.capture_size();
+    let channels = cap.channels_count();
+    for rect in [None, Some(Rect::new(0, 0, 2, 4)), Some(Rect::new(2, 1, 4, 2))] {
+        let expected = method.score(&frame, size, channels, rect);
+        let score = cap.sharpness(method, rect);
+        assert!((score - expected).abs() <= expected * 0.01 + 1e-3);
+    }
+    assert!(cap.sharpness(method, None) > 0.0);
+}
+
+#[rstest]
+#[wasm::test]
+async fn hash_small_capture_matches_full_frame(
+    #[values(
+        HtmlContextOptions2D::default().into(),
+        HtmlContextOptionsGL::default().into()
+    )]
+    options: SupportedOptions,
+) {
+    let setup = CaptureSetup::from_size(64, 64);
+    let (width, height) = HashMethod::Average.input_size();
+    let small = create_capture(width, height, options);
+    let full = create_capture(64, 64, options);
+
+    setup.context.set_fill_style(&"rgb(0, 0, 0)".into());
+    setup.context.fill_rect(0.0, 0.0, 64.0, 64.0);
+    setup.context.set_fill_style(&"rgb(255, 255, 255)".into());
+    setup.context.fill_rect(0.0, 0.0, 32.0, 32.0);
+    wait_next_frame(&setup.video).await;
+    small.capture(&setup.video, CaptureMode::Fill);
+    full.capture(&setup.video, CaptureMode::Fill);
+
+    let hash = HashMethod::Average.hash_capture(&small);
+    assert!(hash.is_similar(&HashMethod::Average.hash_capture(&full), 2));
+    assert_eq!(hash.0 >> 60, 0b1111);
+}
+
+#[rstest]
+#[wasm::test]
+async fn scene_detector_finds_cut(
+    #[values(
+        HtmlContextOptions2D::default().into(),
+        HtmlContextOptionsGL::default().version(GLVersion::WebGL2).into()
+    )]
+    options: SupportedOptions,
+) {
+    let setup = CaptureSetup::from_size(8, 4);
+    let cap = create_capture(8, 4, options);
+    let mut detector = SceneDetector::new(SceneOptions::default().min_interval(0.0));
+
+    for (timestamp, color) in [(0.0, "rgb(0, 0, 0)"), (1.0, "rgb(0, 0, 0)"), (2.0, "rgb(255, 255, 255)")] {
+        setup.context.set_fill_style(&color.into());
+        setup.context.fill_rect(0.0, 0.0, 8.0, 4.0);
+        wait_next_frame(&setup.video).await;
+        cap.capture(&setup.video, CaptureMode::Fill);
+
+        let change = detector.detect(&cap, timestamp);
+        assert_eq!(change.is_some(), timestamp != 1.0);
+    }
+}
+
+#[rstest]
+#[wasm::test]
+async fn capture_warp_rectifies_quadrant(
+    #[values(
+        HtmlContextOptions2D::default().will_read_frequently(true).into(),
+        OffscreenContextOptions2D::default().will_read_frequently(true).into(),
+        OffscreenContextOptionsGL::default().into()
+    )]
+    options: SupportedOptions,
+) {
+    let setup = CaptureSetup::from_size(DEFAULT_WIDTH, DEFAULT_HEIGHT);
+    let (w, h) = setup.capture_size();
+    let cap = create_capture(w, h, options);
+
+    let (x, y) = ((w / 2) as f64, (h / 2) as f64);
+
+    setup.context.set_fill_style(&"rgb(0, 0, 255)".into());
+    setup.context.fill_rect(0.0, 0.0, w as f64, h as f64);
+
+    setup.context.set_fill_style(&"rgb(255, 0, 0)".into());
+    setup.context.fill_rect(x, 0.0, x, y);
+
+    wait_next_frame(&setup.video).await;
+
+    // inset the top-right quadrant to stay clear of the blended edges
+    let warp = Warp::new(
+        [
+            (x + 4.0, 4.0),
+            (2.0 * x - 4.0, 4.0),
+            (2.0 * x - 4.0, y - 4.0),
+            (x + 4.0, y - 4.0),
+        ],
+        16,
+        8,
+    );
+    assert_eq!(cap.capture_warp(&setup.video, &warp, CaptureMode::Adjust), (16, 8));
+
+    let data = cap.image().unwrap().into_rgba8();
+    for pixel in data.pixels() {
+        assert_eq!(pixel, &Rgba([255, 0, 0, 255]));
+    }
+}
+
+#[rstest]
+#[wasm::test]
+async fn capture_warp_pinhole_covers_area(
+    #[values(
+        HtmlContextOptions2D::default().will_read_frequently(true).into(),
+        OffscreenContextOptions2D::default().will_read_frequently(true).into()
+    )]
+    options: SupportedOptions,
+) {
+    let setup = CaptureSetup::from_size(DEFAULT_WIDTH, DEFAULT_HEIGHT);
+    let (w, h) = setup.capture_size();
+    let cap = create_capture(8, 8, options);
+
+    setup.context.set_fill_style(&"rgb(255, 0, 0)".into());
+    setup.context.fill_rect(0.0, 0.0, w as f64, h as f64);
+
+    wait_next_frame(&setup.video).await;
+
+    // the wider warp output is centered and cropped on the sides
+    let warp = Warp::new([(4.0, 4.0), (36.0, 4.0), (36.0, 20.0), (4.0, 20.0)], 16, 8);
+    assert_eq!(cap.capture_warp(&setup.video, &warp, CaptureMode::Pinhole), (8, 8));
+
+    let data = cap.image().unwrap().into_rgba8();
+    for pixel in data.pixels() {
+        assert_eq!(pixel, &Rgba([255, 0, 0, 255]));
+    }
+}
+
+#[rstest]
+#[wasm::test]
+fn capture_stream_from_html_canvas(
+    #[values(
+        HtmlContextOptions2D::default().into(),
+        HtmlContextOptionsGL::default().into(),
+        HtmlContextOptionsGL::default().version(GLVersion::WebGL2).into()
+    )]
+    options: SupportedOptions,
+    #[values(StreamMode::Auto, StreamMode::Rate(10.0), StreamMode::Manual)] mode: StreamMode,
+) {
+    let cap = create_capture(DEFAULT_WIDTH, DEFAULT_HEIGHT, options);
+
+    let stream = cap.capture_stream(mode).unwrap();
+    assert_eq!(stream.stream().get_video_tracks().length(), 1);
+    stream.request_frame();
+    stream.stop();
+
+    let offscreen = create_capture(
+        DEFAULT_WIDTH,
+        DEFAULT_HEIGHT,
+        OffscreenContextOptions2D::default().into(),
+    );
+    assert!(offscreen.capture_stream(mode).is_err());
+}
+
+#[rstest]
+#[wasm::test]
+fn video_frame_wraps_capture(
+    #[values(
+        HtmlContextOptions2D::default().into(),
+        OffscreenContextOptions2D::default().into(),
+        OffscreenContextOptionsGL::default().version(GLVersion::WebGL2).into()
+    )]
+    options: SupportedOptions,
+) {
+    let cap = create_capture(16, 8, options);
+
+    let frame = cap.video_frame(0.5).unwrap();
+    assert_eq!((frame.display_width(), frame.display_height()), (16, 8));
+    assert_eq!(frame.timestamp(), 500_000.0);
+    frame.close();
+
+    let frame = buffer_frame(&cap.data(), cap.capture_size(), cap.channels_count(), 1.0).unwrap();
+    assert_eq!((frame.coded_width(), frame.coded_height()), (16, 8));
+    frame.close();
+}
+
+#[rstest]
+#[wasm::test]
+fn pooled_data_matches_data(
+    #[values(
+        HtmlContextOptions2D::default().into(),
+        OffscreenContextOptions2D::default().into(),
+        HtmlContextOptionsGL::default().into()
+    )]
+    options: SupportedOptions,
+) {
+    let cap = create_capture(16, 8, options);
+    let pool = BufferPool::new(1);
+
+    let buffer = cap.data_pooled(&pool);
+    assert_eq!(&*buffer, cap.data().as_slice());
+    drop(buffer);
+    assert_eq!(pool.available(), 1);
+
+    cap.set_capture_size(8, 4);
+    assert_eq!(cap.data_pooled(&pool).len(), cap.buffer_size());
+}
+
+#[rstest]
+#[wasm::test]
+fn retrieve_follows_layout(
+    #[values(
+        HtmlContextOptions2D::default().into(),
+        HtmlContextOptionsGL::default().into(),
+        OffscreenContextOptionsGL::default().version(GLVersion::WebGL2).into()
+    )]
+    options: SupportedOptions,
+    #[values(1, 8, 64)] alignment: usize,
+) {
+    let cap = create_capture(5, 3, options);
+    let packed = cap.packed_data();
+
+    let layout = FrameLayout::default()
+        .alignment(alignment)
+        .row_order(RowOrder::BottomUp)
+        .channel_order(ChannelOrder::Bgra);
+    cap.set_layout(layout);
+    assert_eq!(cap.buffer_size(), layout.buffer_size((5, 3), 4));
+
+    let data = cap.data();
+    let mut unpacked = vec![0; packed.len()];
+    layout.pack(&data, (5, 3), 4, &mut unpacked);
+    assert_eq!(unpacked, packed);
+    assert_eq!(cap.packed_data(), packed);
 }
 
 fn animation_frame() -> JsFuture {
@@ -0,0 +1,167 @@
+use std::{
+    cell::Cell,
+    io::{self, Write},
+    rc::Rc,
+};
+
+use browser_video_capture::{frame_to_ycbcr, ycbcr_to_rgba, Y4mChroma, Y4mReader, Y4mWriter};
+
+fn frame(width: u32, height: u32, color: [u8; 4]) -> Vec<u8> {
+    color.repeat((width * height) as usize)
+}
+
+fn assert_close(actual: &[u8], expected: &[u8], tolerance: i32) {
+    assert_eq!(actual.len(), expected.len());
+    for (a, e) in actual.iter().zip(expected.iter()) {
+        assert!(
+            (*a as i32 - *e as i32).abs() <= tolerance,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+}
+
+#[test]
+fn header_and_frame_layout() {
+    let mut writer = Y4mWriter::new(Vec::new(), (3, 3), (30000, 1001));
+    writer.write(&frame(3, 3, [0, 0, 0, 255]), 4).unwrap();
+    let stream = writer.into_inner();
+
+    let header = b"YUV4MPEG2 W3 H3 F30000:1001 Ip A1:1 C420jpeg XCOLORRANGE=LIMITED\n";
+    assert!(stream.starts_with(header));
+    assert_eq!(&stream[header.len()..header.len() + 6], b"FRAME\n");
+    // 3x3 luma and two 2x2 chroma planes
+    assert_eq!(stream.len(), header.len() + 6 + 9 + 2 * 4);
+    assert_eq!(stream[header.len() + 6], 16);
+}
+
+#[test]
+fn roundtrip_444_and_420() {
+    for chroma in [Y4mChroma::C444, Y4mChroma::C420].iter() {
+        let colors = [[255, 0, 0, 255], [10, 200, 90, 255], [128, 128, 128, 255]];
+
+        let mut writer = Y4mWriter::new(Vec::new(), (4, 2), (25, 1)).chroma(*chroma);
+        for color in colors.iter() {
+            writer.write(&frame(4, 2, *color), 4).unwrap();
+        }
+        assert_eq!(writer.frames(), 3);
+
+        let stream = writer.into_inner();
+        let mut reader = Y4mReader::new(&stream[..]).unwrap();
+        assert_eq!(reader.size(), (4, 2));
+        assert_eq!(reader.rate(), (25, 1));
+        assert_eq!(reader.chroma(), *chroma);
+
+        for color in colors.iter() {
+            let rgba = reader.read_rgba().unwrap().unwrap();
+            assert_close(&rgba, &frame(4, 2, *color), 2);
+        }
+        assert_eq!(reader.read_rgba().unwrap(), None);
+    }
+}
+
+#[test]
+fn gray_frames_have_neutral_chroma() {
+    let mut writer = Y4mWriter::new(Vec::new(), (2, 2), (1, 1)).chroma(Y4mChroma::C444);
+    writer.write(&[0, 85, 170, 255], 1).unwrap();
+
+    let stream = writer.into_inner();
+    let mut reader = Y4mReader::new(&stream[..]).unwrap();
+    let planes = reader.read_frame().unwrap().unwrap();
+
+    assert_eq!(&planes[..4], &[16, 89, 162, 235]);
+    assert!(planes[4..].iter().all(|&value| value == 128));
+}
+
+#[test]
+fn timestamps_fill_gaps_and_drop_duplicates() {
+    let mut writer = Y4mWriter::new(Vec::new(), (1, 1), (10, 1)).chroma(Y4mChroma::C444);
+
+    writer.write_at(&[255, 255, 255, 255], 4, 5.0).unwrap();
+    // 0.3 seconds later, two slots are repeated
+    writer.write_at(&[0, 0, 0, 255], 4, 5.3).unwrap();
+    // the slot is taken
+    writer.write_at(&[255, 0, 0, 255], 4, 5.31).unwrap();
+    assert_eq!(writer.frames(), 4);
+
+    let stream = writer.into_inner();
+    let mut reader = Y4mReader::new(&stream[..]).unwrap();
+    let lumas: Vec<u8> = std::iter::from_fn(|| reader.read_frame().unwrap())
+        .map(|planes| planes[0])
+        .collect();
+
+    assert_eq!(lumas, [235, 235, 235, 16]);
+}
+
+#[test]
+fn mismatched_frame_is_rejected() {
+    let mut writer = Y4mWriter::new(Vec::new(), (2, 2), (30, 1));
+    assert!(writer.write(&[0; 12], 4).is_err());
+    assert!(Y4mReader::new(&b"P6\n"[..]).is_err());
+}
+
+#[test]
+fn invalid_buffers_are_rejected() {
+    let invalid = |result: io::Result<Vec<u8>>| {
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    };
+    invalid(frame_to_ycbcr(&[0; 12], (2, 2), 4, Y4mChroma::C420));
+    invalid(frame_to_ycbcr(&[], (2, 2), 0, Y4mChroma::C420));
+    invalid(frame_to_ycbcr(
+        &[],
+        (u32::MAX, u32::MAX),
+        4,
+        Y4mChroma::C444,
+    ));
+    invalid(ycbcr_to_rgba(&[0; 5], (2, 2), Y4mChroma::C420));
+
+    let mut writer = Y4mWriter::new(Vec::new(), (65536, 65536), (30, 1));
+    assert!(writer.write(&[], 1).is_err());
+    assert!(writer.write(&[], 0).is_err());
+    assert!(writer.write_at(&[0; 4], 4, 0.0).is_err());
+    assert!(writer.into_inner().is_empty());
+}
+
+#[test]
+fn malformed_headers_are_rejected() {
+    let header = "YUV4MPEG2 W2 H2 \u{e9}x C420jpeg\nFRAME\n";
+    let mut stream = header.as_bytes().to_vec();
+    stream.extend_from_slice(&[0; 6]);
+    let mut reader = Y4mReader::new(&stream[..]).unwrap();
+    assert_eq!(reader.size(), (2, 2));
+    assert!(reader.read_frame().unwrap().is_some());
+
+    let huge = format!("YUV4MPEG2 W{} H{} C444\n", u32::MAX, u32::MAX);
+    assert!(Y4mReader::new(huge.as_bytes()).is_err());
+}
+
+/// Writer failing while the flag is set.
+struct FailingWriter(Rc<Cell<bool>>, Vec<u8>);
+
+impl Write for FailingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.0.get() {
+            return Err(io::Error::other("failed"));
+        }
+        self.1.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn failed_repeat_keeps_last_frame() {
+    let fail = Rc::new(Cell::new(false));
+    let mut writer = Y4mWriter::new(FailingWriter(fail.clone(), Vec::new()), (1, 1), (30, 1));
+
+    writer.write_at(&[255; 4], 4, 0.0).unwrap();
+    fail.set(true);
+    assert!(writer.write_at(&[0; 4], 4, 2.0 / 30.0).is_err());
+    fail.set(false);
+    writer.write_at(&[0; 4], 4, 2.0 / 30.0).unwrap();
+
+    assert_eq!(writer.frames(), 3);
+}